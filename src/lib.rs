@@ -19,7 +19,8 @@
 //! use ::axum::Router;
 //! use ::axum::extract::Json;
 //! use ::axum::routing::put;
-//! use ::axum_test::Server;
+//! use ::axum_test::TestServer;
+//! use ::kantan::Server;
 //! use ::serde_json::json;
 //! use ::serde_json::Value;
 //!
@@ -31,7 +32,10 @@
 //!     .route("/users", put(put_user))
 //!     .into_make_service();
 //!
-//! let server = Server::new(my_app)
+//! let test_server = TestServer::new(my_app)
+//!     .unwrap();
+//!
+//! let server = Server::new(test_server.server_address())
 //!     .unwrap();
 //!
 //! let response = server.put("/users")
@@ -52,17 +56,16 @@
 //! ```rust
 //! # ::tokio_test::block_on(async {
 //! use ::axum::Router;
-//! use ::axum_test::Server;
-//! use ::axum_test::ServerConfig;
+//! use ::kantan::Server;
+//! use ::kantan::ServerConfig;
 //!
-//! let my_app = Router::new()
-//!     .into_make_service();
+//! let my_app = Router::new();
 //!
 //! let config = ServerConfig {
 //!     save_cookies: true,
 //!     ..ServerConfig::default()
 //! };
-//! let server = Server::new_with_config(my_app, config)
+//! let server = Server::new_with_router_and_config(my_app, config)
 //!     .unwrap();
 //! # })
 //! ```
@@ -79,23 +82,23 @@
 //!
 //! You can set a default type for all `Request` objects to use,
 //! by setting the `default_content_type` in the `ServerConfig`.
-//! When creating the `Server` instance, using `new_with_config`.
+//! When creating the `Server` instance, using `new_with_config`
+//! (or `new_with_router_and_config`).
 //!
 //! ```rust
 //! # ::tokio_test::block_on(async {
 //! use ::axum::Router;
-//! use ::axum_test::Server;
-//! use ::axum_test::ServerConfig;
+//! use ::kantan::Server;
+//! use ::kantan::ServerConfig;
 //!
-//! let my_app = Router::new()
-//!     .into_make_service();
+//! let my_app = Router::new();
 //!
 //! let config = ServerConfig {
 //!     default_content_type: Some("application/json".to_string()),
 //!     ..ServerConfig::default()
 //! };
 //!
-//! let server = Server::new_with_config(my_app, config)
+//! let server = Server::new_with_router_and_config(my_app, config)
 //!     .unwrap();
 //! # })
 //! ```
@@ -113,6 +116,7 @@
 //! use ::axum::Router;
 //! use ::axum::extract::Json;
 //! use ::axum::routing::put;
+//! use ::axum_test::TestServer;
 //! use ::kantan::Server;
 //! use ::serde_json::json;
 //! use ::serde_json::Value;
@@ -125,7 +129,10 @@
 //!     .route("/users", put(put_user))
 //!     .into_make_service();
 //!
-//! let server = Server::new(my_app)
+//! let test_server = TestServer::new(my_app)
+//!     .unwrap();
+//!
+//! let server = Server::new(test_server.server_address())
 //!     .unwrap();
 //!
 //! let response = server.put("/users")
@@ -150,14 +157,97 @@
 mod server;
 pub use self::server::*;
 
+mod server_config;
+pub use self::server_config::*;
+
 mod request;
 pub use self::request::*;
 
 mod response;
 pub use self::response::*;
 
+mod multipart_form;
+pub use self::multipart_form::*;
+
 pub use ::hyper::http;
 
+#[cfg(test)]
+mod test_utils {
+    //! Fixtures shared by the tests below.
+
+    use super::*;
+
+    use ::axum::extract::RawQuery;
+    use ::axum::http::header::CONTENT_TYPE;
+    use ::axum::http::header::COOKIE;
+    use ::axum::http::HeaderMap;
+    use ::axum::http::Uri;
+    use ::axum::http::Version;
+    use ::axum::Router;
+    use ::std::time::Duration;
+    use ::tokio::time::sleep;
+
+    /// Runs the app given in the background, and returns a `Server` for it.
+    pub(crate) fn new_server(app: Router) -> Server {
+        Server::new_with_router(app).expect("Should create server")
+    }
+
+    /// Runs the app given in the background, and returns a `Server` for it
+    /// using the config given.
+    pub(crate) fn new_server_with_config(app: Router, config: ServerConfig) -> Server {
+        Server::new_with_router_and_config(app, config).expect("Should create server")
+    }
+
+    pub(crate) async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    pub(crate) async fn get_fast() -> &'static str {
+        "fast"
+    }
+
+    pub(crate) async fn get_slow() -> &'static str {
+        sleep(Duration::from_secs(2)).await;
+        "slow"
+    }
+
+    pub(crate) async fn get_version(version: Version) -> String {
+        format!("{:?}", version)
+    }
+
+    /// Returns the path requested, along with the query if there was one.
+    pub(crate) async fn get_path(uri: Uri, RawQuery(query): RawQuery) -> String {
+        match query {
+            Some(query) => format!("{}?{}", uri.path(), query),
+            None => uri.path().to_string(),
+        }
+    }
+
+    pub(crate) async fn post_echo(body: String) -> String {
+        body
+    }
+
+    pub(crate) async fn post_content_type(headers: HeaderMap) -> String {
+        headers
+            .get(CONTENT_TYPE)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "header-not-found".to_string())
+    }
+
+    /// Returns all of the cookies sent, sorted so they are simple to compare.
+    pub(crate) async fn get_cookie_header(headers: HeaderMap) -> String {
+        let mut cookies: Vec<String> = headers
+            .get_all(COOKIE)
+            .iter()
+            .flat_map(|h| h.to_str().unwrap().split("; "))
+            .map(|c| c.to_string())
+            .collect();
+        cookies.sort();
+
+        cookies.join("; ")
+    }
+}
+
 #[cfg(test)]
 mod test_get {
     use super::*;
@@ -183,7 +273,7 @@ mod test_get {
 
         // Get the request.
         let server = Server::new(server_address).expect("Should create server");
-        let text = server.get(&"/ping").await.text();
+        let text = server.get("/ping").await.text();
 
         assert_eq!(text, "pong!");
     }
@@ -199,6 +289,8 @@ mod test_content_type {
     use ::axum::Router;
     use ::axum_test::TestServer;
 
+    use crate::test_utils::new_server_with_config;
+
     async fn get_content_type(headers: HeaderMap) -> String {
         headers
             .get(CONTENT_TYPE)
@@ -219,7 +311,7 @@ mod test_content_type {
 
         // Get the request.
         let server = Server::new(server_address).expect("Should create server");
-        let text = server.get(&"/content_type").await.text();
+        let text = server.get("/content_type").await.text();
 
         assert_eq!(text, "");
     }
@@ -238,14 +330,55 @@ mod test_content_type {
         // Get the request.
         let server = Server::new(server_address).expect("Should create server");
         let response = server
-            .get(&"/content_type")
-            .content_type(&"application/json")
+            .get("/content_type")
+            .content_type("application/json")
             .await;
 
         assert_eq!(response.status_code(), ::hyper::StatusCode::OK);
         let text = response.text();
         assert_eq!(text, "application/json");
     }
+
+    fn new_server_with_default_content_type(content_type: &str) -> Server {
+        let app = Router::new().route("/content_type", get(get_content_type));
+        let config = ServerConfig {
+            default_content_type: Some(content_type.to_string()),
+            ..ServerConfig::default()
+        };
+
+        new_server_with_config(app, config)
+    }
+
+    #[tokio::test]
+    async fn it_should_use_server_default_when_not_set_on_request() {
+        let server = new_server_with_default_content_type("application/xml");
+        let text = server.get("/content_type").await.text();
+
+        assert_eq!(text, "application/xml");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_server_default_when_sending_json_or_text() {
+        let server = new_server_with_default_content_type("application/xml");
+
+        let text = server.get("/content_type").json(&"body").await.text();
+        assert_eq!(text, "application/xml");
+
+        let text = server.get("/content_type").text("body").await.text();
+        assert_eq!(text, "application/xml");
+    }
+
+    #[tokio::test]
+    async fn it_should_override_server_default_when_set_on_request() {
+        let server = new_server_with_default_content_type("application/xml");
+        let text = server
+            .get("/content_type")
+            .content_type("text/csv")
+            .await
+            .text();
+
+        assert_eq!(text, "text/csv");
+    }
 }
 
 #[cfg(test)]
@@ -261,10 +394,12 @@ mod test_cookies {
     use ::axum_test::TestServer;
     use ::hyper::body::to_bytes;
 
-    const TEST_COOKIE_NAME: &'static str = &"test-cookie";
+    use crate::test_utils::new_server;
+
+    const TEST_COOKIE_NAME: &str = "test-cookie";
 
     async fn get_cookie(cookies: CookieJar) -> (CookieJar, String) {
-        let cookie = cookies.get(&TEST_COOKIE_NAME);
+        let cookie = cookies.get(TEST_COOKIE_NAME);
         let cookie_value = cookie
             .map(|c| c.value().to_string())
             .unwrap_or_else(|| "cookie-not-found".to_string());
@@ -283,7 +418,7 @@ mod test_cookies {
         let cookie = AxumCookie::new(TEST_COOKIE_NAME, body_text);
         cookies = cookies.add(cookie);
 
-        (cookies, &"done")
+        (cookies, "done")
     }
 
     #[tokio::test]
@@ -300,10 +435,10 @@ mod test_cookies {
 
         // Get the request.
         let server = Server::new(server_address).expect("Should create server");
-        server.put(&"/cookie").text(&"new-cookie").await;
+        server.put("/cookie").text("new-cookie").await;
 
         // Check it comes back.
-        let response_text = server.get(&"/cookie").await.text();
+        let response_text = server.get("/cookie").await.text();
 
         assert_eq!(response_text, "cookie-not-found");
     }
@@ -323,14 +458,5459 @@ mod test_cookies {
         // Create a cookie.
         let server = Server::new(server_address).expect("Should create server");
         server
-            .put(&"/cookie")
-            .text(&"cookie-found!")
+            .put("/cookie")
+            .text("cookie-found!")
             .do_save_cookies()
             .await;
 
         // Check it comes back.
-        let response_text = server.get(&"/cookie").await.text();
+        let response_text = server.get("/cookie").await.text();
+
+        assert_eq!(response_text, "cookie-found!");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_cookies_created_back_up_to_server_when_turned_on_for_server() {
+        // Build an application with a route.
+        let app = Router::new()
+            .route("/cookie", put(put_cookie))
+            .route("/cookie", get(get_cookie));
+
+        // Create a cookie.
+        let mut server = new_server(app);
+        server.save_cookies();
+        server.put("/cookie").text("cookie-found!").await;
+
+        // Check it comes back.
+        let response_text = server.get("/cookie").await.text();
 
         assert_eq!(response_text, "cookie-found!");
     }
+
+    #[tokio::test]
+    async fn it_should_not_pass_cookies_created_back_up_to_server_when_turned_off_for_server() {
+        // Build an application with a route.
+        let app = Router::new()
+            .route("/cookie", put(put_cookie))
+            .route("/cookie", get(get_cookie));
+
+        // Create a cookie.
+        let mut server = new_server(app);
+        server.save_cookies();
+        server.do_not_save_cookies();
+        server.put("/cookie").text("new-cookie").await;
+
+        // Check it comes back.
+        let response_text = server.get("/cookie").await.text();
+
+        assert_eq!(response_text, "cookie-not-found");
+    }
+}
+
+#[cfg(test)]
+mod test_add_header {
+    use super::*;
+
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::hyper::http::HeaderName;
+    use ::hyper::http::HeaderValue;
+
+    use crate::test_utils::new_server;
+
+    async fn get_request_id(headers: HeaderMap) -> String {
+        headers
+            .get("x-request-id")
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "header-not-found".to_string())
+    }
+
+    #[tokio::test]
+    async fn it_should_send_header_added_from_strings() {
+        // Build an application with a route.
+        let app = Router::new().route("/header", get(get_request_id));
+
+        // Get the request.
+        let server = new_server(app);
+        let text = server
+            .get("/header")
+            .add_header("x-request-id", "my-request")
+            .await
+            .text();
+
+        assert_eq!(text, "my-request");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_header_added_from_header_types() {
+        // Build an application with a route.
+        let app = Router::new().route("/header", get(get_request_id));
+
+        // Get the request.
+        let server = new_server(app);
+        let text = server
+            .get("/header")
+            .add_header(
+                HeaderName::from_static("x-request-id"),
+                HeaderValue::from_static("my-typed-request"),
+            )
+            .await
+            .text();
+
+        assert_eq!(text, "my-typed-request");
+    }
+
+    async fn get_all_cache_control(headers: HeaderMap) -> String {
+        let values: Vec<&str> = headers
+            .get_all("cache-control")
+            .iter()
+            .map(|h| h.to_str().unwrap())
+            .collect();
+
+        values.join(" | ")
+    }
+
+    fn new_cache_control_server() -> Server {
+        let app = Router::new().route("/cache-control", get(get_all_cache_control));
+        new_server(app)
+    }
+
+    #[tokio::test]
+    async fn it_should_send_all_values_when_header_added_twice() {
+        let server = new_cache_control_server();
+        let text = server
+            .get("/cache-control")
+            .add_header("cache-control", "no-cache")
+            .add_header("cache-control", "no-store")
+            .await
+            .text();
+
+        assert_eq!(text, "no-cache | no-store");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_all_headers_added_in_bulk() {
+        let server = new_cache_control_server();
+        let text = server
+            .get("/cache-control")
+            .add_headers([
+                ("cache-control", "no-cache"),
+                ("cache-control", "no-store"),
+                ("cache-control", "max-age=0"),
+            ])
+            .await
+            .text();
+
+        assert_eq!(text, "no-cache | no-store | max-age=0");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_typed_headers_added_in_bulk() {
+        let server = new_cache_control_server();
+        let headers = vec![
+            (
+                HeaderName::from_static("cache-control"),
+                HeaderValue::from_static("no-cache"),
+            ),
+            (
+                HeaderName::from_static("x-request-id"),
+                HeaderValue::from_static("my-request"),
+            ),
+        ];
+        let text = server
+            .get("/cache-control")
+            .add_headers(headers)
+            .await
+            .text();
+
+        assert_eq!(text, "no-cache");
+    }
+}
+
+#[cfg(test)]
+mod test_expect_failure {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::net::TcpListener;
+
+    use crate::test_utils::get_ping;
+    use crate::test_utils::new_server;
+
+    /// Returns the address of a port that nothing is listening on.
+    fn new_closed_server_address() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind to a free port");
+        let socket_address = listener.local_addr().expect("Should get the bound address");
+
+        format!("http://{}", socket_address)
+    }
+
+    #[tokio::test]
+    async fn it_should_return_response_when_request_fails_and_expecting_failure() {
+        let server = Server::new(new_closed_server_address()).expect("Should create server");
+        let response = server.get("/ping").expect_failure().await;
+
+        assert_eq!(response.text(), "");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_transport_error_when_request_fails_and_expecting_failure() {
+        let server = Server::new(new_closed_server_address()).expect("Should create server");
+        let response = server.get("/ping").expect_failure().await;
+        let error = response
+            .transport_error()
+            .expect("Should have a transport error");
+
+        let is_connection_refused = error.chain().any(|cause| {
+            cause
+                .downcast_ref::<::std::io::Error>()
+                .is_some_and(|io_err| io_err.kind() == ::std::io::ErrorKind::ConnectionRefused)
+        });
+        assert!(
+            is_connection_refused,
+            "Expected connection refused, got {:?}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_have_transport_error_when_request_succeeds() {
+        let app = Router::new().route("/ping", get(get_ping));
+        let server = new_server(app);
+        let response = server.get("/ping").await;
+
+        assert!(response.transport_error().is_none());
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_when_request_fails_and_not_expecting_failure() {
+        let server = Server::new(new_closed_server_address()).expect("Should create server");
+        let _ = server.get("/ping").await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_when_request_succeeds_and_expecting_failure() {
+        // Build an application with a route.
+        let app = Router::new().route("/ping", get(get_ping));
+
+        // Get the request.
+        let server = new_server(app);
+        let _ = server.get("/ping").expect_failure().await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_reading_status_code_of_failed_request() {
+        let server = Server::new(new_closed_server_address()).expect("Should create server");
+        let response = server.get("/ping").expect_failure().await;
+
+        let _ = response.status_code();
+    }
+}
+
+#[cfg(test)]
+mod test_expect_failure_kind {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::net::TcpListener;
+    use ::std::time::Duration;
+
+    use crate::test_utils::get_ping;
+    use crate::test_utils::get_slow;
+    use crate::test_utils::new_server;
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/ping", get(get_ping))
+            .route("/slow", get(get_slow))
+    }
+
+    /// Returns a server sending requests to a port that nothing is listening on.
+    fn new_closed_server() -> Server {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind to a free port");
+        let socket_address = listener.local_addr().expect("Should get the bound address");
+
+        Server::new(format!("http://{}", socket_address)).expect("Should create server")
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_expect_connection_refused_when_refused() {
+        let server = new_closed_server();
+        let response = server.get("/ping").expect_connection_refused().await;
+
+        assert!(response.transport_error().is_some());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "to have its connection refused")]
+    async fn it_should_panic_on_expect_connection_refused_when_timed_out() {
+        let server = new_server(new_app());
+        let _ = server
+            .get("/slow")
+            .timeout(Duration::from_millis(50))
+            .expect_connection_refused()
+            .await;
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_expect_timeout_when_timed_out() {
+        let server = new_server(new_app());
+        let response = server
+            .get("/slow")
+            .timeout(Duration::from_millis(50))
+            .expect_timeout()
+            .await;
+
+        assert!(response.transport_error().is_some());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "to time out")]
+    async fn it_should_panic_on_expect_timeout_when_refused() {
+        let server = new_closed_server();
+        let _ = server.get("/ping").expect_timeout().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "to fail, but it succeeded")]
+    async fn it_should_panic_on_expect_timeout_when_succeeded() {
+        let server = new_server(new_app());
+        let _ = server.get("/ping").expect_timeout().await;
+    }
+}
+
+#[cfg(test)]
+mod test_query {
+    use ::axum::extract::RawQuery;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::serde::Serialize;
+
+    use crate::test_utils::new_server;
+
+    async fn get_query(RawQuery(query): RawQuery) -> String {
+        query.unwrap_or_else(|| "query-not-found".to_string())
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/query", get(get_query))
+    }
+
+    #[tokio::test]
+    async fn it_should_append_multiple_query_params() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/query")
+            .query_param("first", "1")
+            .query_param("second", "2")
+            .await
+            .text();
+
+        assert_eq!(text, "first=1&second=2");
+    }
+
+    #[tokio::test]
+    async fn it_should_append_repeated_keys_in_order() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/query")
+            .query_params([("id", "1"), ("id", "2"), ("sort", "name"), ("id", "3")])
+            .await
+            .text();
+
+        assert_eq!(text, "id=1&id=2&sort=name&id=3");
+    }
+
+    #[tokio::test]
+    async fn it_should_append_query_params_of_strings_after_existing_query() {
+        let server = new_server(new_app());
+        let params = vec![
+            ("tag".to_string(), "a b".to_string()),
+            ("tag".to_string(), "c&d".to_string()),
+        ];
+        let text = server
+            .get("/query?page=2")
+            .query_params(params)
+            .await
+            .text();
+
+        assert_eq!(text, "page=2&tag=a+b&tag=c%26d");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_change_path_for_empty_query_params() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/query")
+            .query_params(Vec::<(String, String)>::new())
+            .await
+            .text();
+
+        assert_eq!(text, "query-not-found");
+    }
+
+    #[tokio::test]
+    async fn it_should_merge_with_query_already_in_path() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/query?existing=0")
+            .query_param("first", "1")
+            .await
+            .text();
+
+        assert_eq!(text, "existing=0&first=1");
+    }
+
+    #[tokio::test]
+    async fn it_should_url_encode_query_params() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/query")
+            .query_param("search", "hello world&more=yes")
+            .await
+            .text();
+
+        assert_eq!(text, "search=hello+world%26more%3Dyes");
+    }
+
+    #[tokio::test]
+    async fn it_should_serialize_query_from_struct() {
+        #[derive(Serialize)]
+        struct Pagination {
+            page: u32,
+            per_page: u32,
+        }
+
+        let server = new_server(new_app());
+        let text = server
+            .get("/query")
+            .query(&Pagination {
+                page: 2,
+                per_page: 50,
+            })
+            .await
+            .text();
+
+        assert_eq!(text, "page=2&per_page=50");
+    }
+}
+
+#[cfg(test)]
+mod test_form {
+    use ::axum::extract::Form;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::serde::Deserialize;
+    use ::serde::Serialize;
+
+    use crate::test_utils::new_server;
+
+    #[derive(Serialize, Deserialize)]
+    struct User {
+        name: String,
+        age: u32,
+    }
+
+    async fn post_user(Form(user): Form<User>) -> String {
+        format!("{} is {}", user.name, user.age)
+    }
+
+    #[tokio::test]
+    async fn it_should_send_form_to_server() {
+        // Build an application with a route.
+        let app = Router::new().route("/user", post(post_user));
+
+        // Get the request.
+        let server = new_server(app);
+        let text = server
+            .post("/user")
+            .form(&User {
+                name: "Terrance Pencilworth".to_string(),
+                age: 42,
+            })
+            .await
+            .text();
+
+        assert_eq!(text, "Terrance Pencilworth is 42");
+    }
+}
+
+#[cfg(test)]
+mod test_response_json {
+    use ::axum::routing::get;
+    use ::axum::Json;
+    use ::axum::Router;
+    use ::serde::Deserialize;
+    use ::serde::Serialize;
+    use ::serde_json::json;
+
+    use crate::test_utils::new_server;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+    }
+
+    async fn get_user() -> Json<User> {
+        Json(User {
+            name: "Terrance Pencilworth".to_string(),
+        })
+    }
+
+    async fn get_not_json() -> &'static str {
+        "this is not json"
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/user", get(get_user))
+            .route("/not-json", get(get_not_json))
+    }
+
+    #[tokio::test]
+    async fn it_should_deserialize_json_response() {
+        let server = new_server(new_app());
+        let user: User = server.get("/user").await.json();
+
+        assert_eq!(
+            user,
+            User {
+                name: "Terrance Pencilworth".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_deserialize_json_response_into_owned_value() {
+        let server = new_server(new_app());
+        let user: User = server.get("/user").await.into_json();
+
+        assert_eq!(
+            user,
+            User {
+                name: "Terrance Pencilworth".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_on_into_json_when_response_is_not_json() {
+        let server = new_server(new_app());
+        let _: User = server.get("/not-json").await.into_json();
+    }
+
+    #[tokio::test]
+    async fn it_should_return_error_with_body_when_response_is_not_json() {
+        let server = new_server(new_app());
+        let result = server.get("/not-json").await.maybe_json::<User>();
+
+        let error = format!("{:?}", result.unwrap_err());
+        assert!(error.contains("this is not json"));
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_when_response_is_not_json() {
+        let server = new_server(new_app());
+        let _: User = server.get("/not-json").await.json();
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_json_when_json_matches() {
+        let server = new_server(new_app());
+
+        server.get("/user").await.assert_json(&User {
+            name: "Terrance Pencilworth".to_string(),
+        });
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected JSON of request")]
+    async fn it_should_fail_assert_json_when_json_differs() {
+        let server = new_server(new_app());
+
+        server.get("/user").await.assert_json(&User {
+            name: "Someone Else".to_string(),
+        });
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_json_eq_when_json_matches() {
+        let server = new_server(new_app());
+
+        server.get("/user").await.assert_json_eq(json!({
+            "name": "Terrance Pencilworth",
+        }));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "\"name\": \"Someone Else\"")]
+    async fn it_should_fail_assert_json_eq_with_pretty_json_when_json_differs() {
+        let server = new_server(new_app());
+
+        server.get("/user").await.assert_json_eq(json!({
+            "name": "Someone Else",
+        }));
+    }
+}
+
+#[cfg(test)]
+mod test_assert_status {
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::hyper::StatusCode;
+
+    use crate::test_utils::new_server;
+
+    async fn get_created() -> StatusCode {
+        StatusCode::CREATED
+    }
+
+    async fn get_ok() -> StatusCode {
+        StatusCode::OK
+    }
+
+    async fn get_error() -> (StatusCode, &'static str) {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "database connection failed",
+        )
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/created", get(get_created))
+            .route("/ok", get(get_ok))
+            .route("/error", get(get_error))
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_status_success_for_2xx() {
+        let server = new_server(new_app());
+
+        server.get("/created").await.assert_status_success();
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_fail_assert_status_success_for_404() {
+        let server = new_server(new_app());
+
+        server.get("/not-found").await.assert_status_success();
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "/error, received 500 Internal Server Error, with body 'database connection failed'"
+    )]
+    async fn it_should_show_path_and_body_when_assert_status_ok_fails() {
+        let server = new_server(new_app());
+
+        server.get("/error").await.assert_status_ok();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "with body 'database connection failed'")]
+    async fn it_should_show_body_when_assert_status_success_fails() {
+        let server = new_server(new_app());
+
+        server.get("/error").await.assert_status_success();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "with body 'database connection failed'")]
+    async fn it_should_show_body_when_assert_status_in_range_fails() {
+        let server = new_server(new_app());
+
+        server.get("/error").await.assert_status_in_range(200..=299);
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_status_for_matching_status() {
+        let server = new_server(new_app());
+
+        server
+            .get("/ok")
+            .await
+            .assert_status_ok()
+            .assert_status(StatusCode::OK);
+        server.get("/not-found").await.assert_status_not_found();
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_fail_assert_status_for_different_status() {
+        let server = new_server(new_app());
+
+        server.get("/created").await.assert_status_ok();
+    }
+}
+
+#[cfg(test)]
+mod test_response_headers {
+    use ::axum::http::header::LOCATION;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(LOCATION, "/home".parse().unwrap());
+        headers.append("x-tag", "first".parse().unwrap());
+        headers.append("x-tag", "second".parse().unwrap());
+
+        headers
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/headers", get(get_headers))
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_header_when_header_matches() {
+        let server = new_server(new_app());
+
+        server
+            .get("/headers")
+            .await
+            .assert_header(LOCATION, "/home");
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_fail_assert_header_when_header_differs() {
+        let server = new_server(new_app());
+
+        server
+            .get("/headers")
+            .await
+            .assert_header(LOCATION, "/away");
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_fail_assert_header_when_header_missing() {
+        let server = new_server(new_app());
+
+        server
+            .get("/headers")
+            .await
+            .assert_header("x-missing", "value");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_header_missing_when_header_missing() {
+        let server = new_server(new_app());
+
+        server
+            .get("/headers")
+            .await
+            .assert_header_missing("x-powered-by");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "\"/home\"")]
+    async fn it_should_fail_assert_header_missing_when_header_present() {
+        let server = new_server(new_app());
+
+        server.get("/headers").await.assert_header_missing(LOCATION);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_all_values_for_repeated_header() {
+        let server = new_server(new_app());
+        let tags = server.get("/headers").await.header_all("x-tag");
+
+        assert_eq!(tags, vec!["first", "second"]);
+    }
+}
+
+#[cfg(test)]
+mod test_new_with_router {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::future::pending;
+    use ::std::time::Duration;
+    use ::tokio::join;
+    use ::tokio::time::sleep;
+    use ::tokio::time::timeout;
+
+    use crate::test_utils::get_ping;
+    use crate::test_utils::new_server;
+
+    #[tokio::test]
+    async fn it_should_run_router_and_send_requests_to_it() {
+        // Build an application with a route.
+        let app = Router::new().route("/ping", get(get_ping));
+
+        // Run the server.
+        let server = new_server(app);
+        let text = server.get("/ping").await.text();
+
+        assert_eq!(text, "pong!");
+    }
+
+    #[test]
+    fn it_should_error_when_created_outside_of_a_runtime() {
+        let app = Router::new().route("/ping", get(get_ping));
+        let result = Server::new_with_router(app);
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_stop_router_when_server_is_dropped() {
+        // Build an application with a route.
+        let app = Router::new().route("/ping", get(get_ping));
+
+        // Run the server, and then stop it.
+        let server = new_server(app);
+        let response = server.get("/ping").await;
+        let server_address = format!("http://{}", response.request_uri().authority().unwrap());
+        ::std::mem::drop(server);
+        ::tokio::task::yield_now().await;
+
+        // Requests should no longer reach it.
+        let server = Server::new(server_address).expect("Should create server");
+        server.get("/ping").expect_failure().await;
+    }
+
+    #[tokio::test]
+    async fn it_should_stop_router_when_dropped_with_request_open() {
+        // Build an application with a route that never finishes.
+        let app = Router::new().route("/hang", get(pending::<()>));
+
+        // Run the server, with a request to it from another client.
+        let server = new_server(app);
+        let other_server = Server::new(server.base_url()).expect("Should create server");
+        let request = timeout(Duration::from_secs(5), other_server.get("/hang").send());
+        let drop_server = async move {
+            sleep(Duration::from_millis(100)).await;
+            ::std::mem::drop(server);
+        };
+
+        // The open request should be stopped along with the app.
+        let (result, _) = join!(request, drop_server);
+        let response = result.expect("Request should end once the app is stopped");
+        assert!(response.is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_authorization {
+    use ::axum::http::header::AUTHORIZATION;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_authorization(headers: HeaderMap) -> String {
+        headers
+            .get(AUTHORIZATION)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "header-not-found".to_string())
+    }
+
+    async fn get_all_authorization(headers: HeaderMap) -> String {
+        headers
+            .get_all(AUTHORIZATION)
+            .iter()
+            .map(|h| h.to_str().unwrap())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/auth", get(get_authorization))
+            .route("/auth/all", get(get_all_authorization))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_basic_auth_with_password() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/auth")
+            .basic_auth("Aladdin", Some("open sesame"))
+            .await
+            .text();
+
+        assert_eq!(text, "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_basic_auth_without_password() {
+        let server = new_server(new_app());
+        let text = server.get("/auth").basic_auth("Aladdin", None).await.text();
+
+        assert_eq!(text, "Basic QWxhZGRpbjo=");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_bearer_token() {
+        let server = new_server(new_app());
+        let text = server.get("/auth").bearer_token("my-token").await.text();
+
+        assert_eq!(text, "Bearer my-token");
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_basic_auth_with_bearer_token() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/auth/all")
+            .basic_auth("Aladdin", None)
+            .bearer_token("my-token")
+            .await
+            .text();
+
+        assert_eq!(text, "Bearer my-token");
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_bearer_token_with_basic_auth() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/auth/all")
+            .bearer_token("my-token")
+            .basic_auth("Aladdin", None)
+            .await
+            .text();
+
+        assert_eq!(text, "Basic QWxhZGRpbjo=");
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_authorization_header_already_added() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/auth/all")
+            .add_header(AUTHORIZATION, "Token old-token")
+            .bearer_token("my-token")
+            .await
+            .text();
+
+        assert_eq!(text, "Bearer my-token");
+    }
+}
+
+#[cfg(test)]
+mod test_methods {
+    use ::axum::http::Method;
+    use ::axum::routing::any;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn any_method(method: Method) -> [(&'static str, String); 1] {
+        [("x-method", method.to_string())]
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/method", any(any_method))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_each_method() {
+        let server = new_server(new_app());
+
+        server.get("/method").await.assert_header("x-method", "GET");
+        server
+            .post("/method")
+            .await
+            .assert_header("x-method", "POST");
+        server.put("/method").await.assert_header("x-method", "PUT");
+        server
+            .patch("/method")
+            .await
+            .assert_header("x-method", "PATCH");
+        server
+            .delete("/method")
+            .await
+            .assert_header("x-method", "DELETE");
+        server
+            .head("/method")
+            .await
+            .assert_header("x-method", "HEAD");
+        server
+            .options("/method")
+            .await
+            .assert_header("x-method", "OPTIONS");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_extension_methods() {
+        let server = new_server(new_app());
+        let method = Method::from_bytes(b"PROPFIND").unwrap();
+
+        server
+            .method(method, "/method")
+            .await
+            .assert_header("x-method", "PROPFIND");
+    }
+}
+
+#[cfg(test)]
+mod test_expect_status {
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::hyper::StatusCode;
+
+    use crate::test_utils::new_server;
+
+    async fn get_created() -> StatusCode {
+        StatusCode::CREATED
+    }
+
+    async fn get_error() -> (StatusCode, &'static str) {
+        (StatusCode::INTERNAL_SERVER_ERROR, "something went wrong")
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/created", get(get_created))
+            .route("/error", get(get_error))
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_expect_success_for_2xx() {
+        let server = new_server(new_app());
+
+        server.get("/created").expect_success().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "something went wrong")]
+    async fn it_should_panic_with_body_on_expect_success_for_5xx() {
+        let server = new_server(new_app());
+
+        server.get("/error").expect_success().await;
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_expect_status_for_matching_status() {
+        let server = new_server(new_app());
+
+        server
+            .get("/created")
+            .expect_status(StatusCode::CREATED)
+            .await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_on_expect_status_for_different_status() {
+        let server = new_server(new_app());
+
+        server.get("/created").expect_status(StatusCode::OK).await;
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_expect_status_in_for_listed_status() {
+        let server = new_server(new_app());
+
+        server
+            .get("/created")
+            .expect_status_in(&[StatusCode::OK, StatusCode::CREATED])
+            .await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "one of status codes 200 OK, 201 Created")]
+    async fn it_should_panic_on_expect_status_in_for_unlisted_status() {
+        let server = new_server(new_app());
+
+        server
+            .get("/error")
+            .expect_status_in(&[StatusCode::OK, StatusCode::CREATED])
+            .await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_on_expect_status_in_for_empty_list() {
+        let server = new_server(new_app());
+
+        server.get("/created").expect_status_in(&[]).await;
+    }
+}
+
+#[cfg(test)]
+mod test_base_path {
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_users() -> &'static str {
+        "users"
+    }
+
+    #[tokio::test]
+    async fn it_should_prefix_base_path_to_requests() {
+        let app = Router::new().route("/api/users", get(get_users));
+        let server = new_server(app).with_base_path("/api");
+
+        let text = server.get("/users").expect_success().await.text();
+
+        assert_eq!(text, "users");
+    }
+}
+
+#[cfg(test)]
+mod test_response_body {
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_text() -> &'static str {
+        "hello"
+    }
+
+    async fn get_invalid_utf8() -> Vec<u8> {
+        vec![0xff, 0xfe, 0xfd]
+    }
+
+    async fn get_no_content() -> StatusCode {
+        StatusCode::NO_CONTENT
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/text", get(get_text))
+            .route("/invalid-utf8", get(get_invalid_utf8))
+            .route("/no-content", get(get_no_content))
+    }
+
+    #[tokio::test]
+    async fn it_should_be_empty_for_no_content() {
+        let server = new_server(new_app());
+        let response = server.get("/no-content").await;
+
+        assert!(response.is_empty());
+        response.assert_empty();
+    }
+
+    #[tokio::test]
+    async fn it_should_be_empty_for_head_request() {
+        let server = new_server(new_app());
+
+        server.head("/text").await.assert_empty();
+    }
+
+    #[tokio::test]
+    async fn it_should_not_be_empty_with_body() {
+        let server = new_server(new_app());
+
+        assert!(!server.get("/text").await.is_empty());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "to be empty, received 5 bytes 'hello'")]
+    async fn it_should_fail_assert_empty_with_body() {
+        let server = new_server(new_app());
+
+        server.get("/text").await.assert_empty();
+    }
+
+    #[tokio::test]
+    async fn it_should_return_body_as_text() {
+        let server = new_server(new_app());
+        let text = server.get("/text").await.text();
+
+        assert_eq!(text, "hello");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_request_path() {
+        let server = new_server(new_app());
+        let response = server.get("/text?page=2").await;
+
+        assert_eq!(response.request_path(), "/text");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_request_path_including_base_path() {
+        let server = new_server(new_app()).with_base_path("/api");
+        let response = server.get("/text").await;
+
+        assert_eq!(response.request_path(), "/api/text");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_owned_body_as_bytes() {
+        let server = new_server(new_app());
+        let bytes = server.get("/text").await.into_bytes();
+
+        assert_eq!(bytes, "hello");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_body_as_bytes() {
+        let server = new_server(new_app());
+        let response = server.get("/invalid-utf8").await;
+
+        assert_eq!(response.as_bytes(), &[0xff, 0xfe, 0xfd]);
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_reading_invalid_utf8_as_text() {
+        let server = new_server(new_app());
+        let _ = server.get("/invalid-utf8").await.text();
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_text_when_body_matches() {
+        let server = new_server(new_app());
+
+        server.get("/text").await.assert_text("hello");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected body of request")]
+    async fn it_should_fail_assert_text_when_body_differs() {
+        let server = new_server(new_app());
+
+        server.get("/text").await.assert_text("goodbye");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_text_contains_when_body_contains() {
+        let server = new_server(new_app());
+
+        server.get("/text").await.assert_text_contains("ell");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "to contain 'bye'")]
+    async fn it_should_fail_assert_text_contains_when_body_does_not_contain() {
+        let server = new_server(new_app());
+
+        server.get("/text").await.assert_text_contains("bye");
+    }
+}
+
+#[cfg(test)]
+mod test_server_config {
+    use super::*;
+
+    use ::axum::http::header::ACCEPT;
+    use ::axum::http::header::CONTENT_TYPE;
+    use ::axum::http::header::COOKIE;
+    use ::axum::http::header::SET_COOKIE;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::hyper::http::HeaderValue;
+
+    use crate::test_utils::new_server_with_config;
+
+    async fn get_accept(headers: HeaderMap) -> String {
+        headers
+            .get(ACCEPT)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "header-not-found".to_string())
+    }
+
+    async fn get_all_accept(headers: HeaderMap) -> String {
+        let values: Vec<&str> = headers
+            .get_all(ACCEPT)
+            .iter()
+            .map(|h| h.to_str().unwrap())
+            .collect();
+
+        if values.is_empty() {
+            "header-not-found".to_string()
+        } else {
+            values.join(", ")
+        }
+    }
+
+    async fn get_content_type(headers: HeaderMap) -> String {
+        headers
+            .get(CONTENT_TYPE)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "header-not-found".to_string())
+    }
+
+    fn new_server_with_default_accept() -> Server {
+        let app = Router::new().route("/accept", get(get_all_accept));
+        let config = ServerConfig {
+            default_headers: vec![(ACCEPT, HeaderValue::from_static("application/json"))],
+            ..ServerConfig::default()
+        };
+
+        new_server_with_config(app, config)
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_default_header_with_request_header() {
+        let server = new_server_with_default_accept();
+        let text = server.get("/accept").accept("text/html").await.text();
+
+        assert_eq!(text, "text/html");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_repeated_request_headers_replacing_a_default() {
+        let server = new_server_with_default_accept();
+        let text = server
+            .get("/accept")
+            .add_header(ACCEPT, "text/html")
+            .add_header(ACCEPT, "text/plain")
+            .await
+            .text();
+
+        assert_eq!(text, "text/html, text/plain");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_default_headers_after_clear_headers() {
+        let server = new_server_with_default_accept();
+        let text = server.get("/accept").clear_headers().await.text();
+
+        assert_eq!(text, "header-not-found");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_headers_added_after_clear_headers() {
+        let server = new_server_with_default_accept();
+        let text = server
+            .get("/accept")
+            .accept("text/plain")
+            .clear_headers()
+            .accept("text/html")
+            .await
+            .text();
+
+        assert_eq!(text, "text/html");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_default_headers_on_requests() {
+        let app = Router::new().route("/accept", get(get_accept));
+        let config = ServerConfig {
+            default_headers: vec![(ACCEPT, HeaderValue::from_static("application/json"))],
+            ..ServerConfig::default()
+        };
+        let server = new_server_with_config(app, config);
+
+        let text = server.get("/accept").await.text();
+
+        assert_eq!(text, "application/json");
+    }
+
+    async fn post_login() -> [(&'static str, &'static str); 1] {
+        [(SET_COOKIE.as_str(), "session=abc123")]
+    }
+
+    async fn get_session(headers: HeaderMap) -> String {
+        headers
+            .get(COOKIE)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "cookie-not-found".to_string())
+    }
+
+    fn new_server_saving_cookies() -> Server {
+        let app = Router::new()
+            .route("/login", post(post_login))
+            .route("/session", get(get_session));
+        let config = ServerConfig {
+            save_cookies: true,
+            ..ServerConfig::default()
+        };
+
+        new_server_with_config(app, config)
+    }
+
+    #[tokio::test]
+    async fn it_should_save_cookies_by_default_when_set_in_config() {
+        let server = new_server_saving_cookies();
+
+        server.post("/login").await;
+        let text = server.get("/session").await.text();
+
+        assert_eq!(text, "session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_save_cookies_when_request_overrides_config() {
+        let server = new_server_saving_cookies();
+
+        server.post("/login").do_not_save_cookies().await;
+        let text = server.get("/session").await.text();
+
+        assert_eq!(text, "cookie-not-found");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_default_content_type_on_requests() {
+        let app = Router::new().route("/content_type", get(get_content_type));
+        let config = ServerConfig {
+            default_content_type: Some("application/json".to_string()),
+            ..ServerConfig::default()
+        };
+        let server = new_server_with_config(app, config);
+
+        let text = server.get("/content_type").await.text();
+
+        assert_eq!(text, "application/json");
+    }
+}
+
+#[cfg(test)]
+mod test_multipart {
+    use super::*;
+
+    use ::axum::extract::Multipart;
+    use ::axum::routing::post;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn post_upload(mut multipart: Multipart) -> String {
+        let mut fields = vec![];
+
+        while let Some(field) = multipart.next_field().await.unwrap() {
+            let name = field.name().unwrap_or_default().to_string();
+            let file_name = field.file_name().unwrap_or("none").to_string();
+            let content_type = field.content_type().unwrap_or("none").to_string();
+            let text = field.text().await.unwrap();
+
+            fields.push(format!("{}:{}:{}:{}", name, file_name, content_type, text));
+        }
+
+        fields.join(",")
+    }
+
+    #[tokio::test]
+    async fn it_should_send_text_fields_and_files() {
+        let app = Router::new().route("/upload", post(post_upload));
+        let server = new_server(app);
+
+        let form = MultipartForm::new()
+            .add_text("name", "Terrance Pencilworth")
+            .add_file("notes", "notes.txt", "text/plain", "some notes");
+        let text = server
+            .post("/upload")
+            .multipart(form)
+            .expect_success()
+            .await
+            .text();
+
+        assert_eq!(
+            text,
+            "name:none:none:Terrance Pencilworth,notes:notes.txt:text/plain:some notes"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_encode_line_breaks_in_names() {
+        let app = Router::new().route("/upload", post(post_upload));
+        let server = new_server(app);
+
+        let form = MultipartForm::new()
+            .add_text("name\r\nX-Injected: yes", "Terrance Pencilworth")
+            .add_file(
+                "notes",
+                "notes.txt\r\nContent-Type: text/html",
+                "text/plain",
+                "some notes",
+            );
+        let text = server
+            .post("/upload")
+            .multipart(form)
+            .expect_success()
+            .await
+            .text();
+
+        assert_eq!(
+            text,
+            "name%0D%0AX-Injected: yes:none:none:Terrance Pencilworth,notes:notes.txt%0D%0AContent-Type: text/html:text/plain:some notes"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "as the content type for file 'notes.txt'")]
+    fn it_should_reject_line_breaks_in_content_type() {
+        let _ = MultipartForm::new().add_file(
+            "notes",
+            "notes.txt",
+            "text/plain\r\nX-Injected: yes",
+            "some notes",
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_response_cookies {
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::axum_extra::extract::cookie::Cookie as AxumCookie;
+    use ::axum_extra::extract::cookie::CookieJar;
+    use ::cookie::time::Duration;
+
+    use crate::test_utils::new_server;
+
+    async fn get_cookies(cookies: CookieJar) -> CookieJar {
+        let session = AxumCookie::build("session", "abc123")
+            .http_only(true)
+            .secure(true)
+            .max_age(Duration::hours(1))
+            .finish();
+        let theme = AxumCookie::new("theme", "dark");
+
+        cookies.add(session).add(theme)
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/cookies", get(get_cookies))
+    }
+
+    #[tokio::test]
+    async fn it_should_return_all_cookies_in_response() {
+        let server = new_server(new_app());
+        let cookies = server.get("/cookies").await.cookies();
+
+        assert_eq!(cookies.get("session").unwrap().value(), "abc123");
+        assert_eq!(cookies.get("theme").unwrap().value(), "dark");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_cookie_attributes() {
+        let server = new_server(new_app());
+        let cookie = server.get("/cookies").await.cookie("session");
+
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.max_age(), Some(Duration::hours(1)));
+    }
+
+    #[tokio::test]
+    async fn it_should_return_none_for_missing_cookie() {
+        let server = new_server(new_app());
+        let cookie = server.get("/cookies").await.maybe_cookie("missing");
+
+        assert!(cookie.is_none());
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_cookie_when_present() {
+        let server = new_server(new_app());
+
+        server.get("/cookies").await.assert_cookie("session");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected cookie missing to be set")]
+    async fn it_should_fail_assert_cookie_when_missing() {
+        let server = new_server(new_app());
+
+        server.get("/cookies").await.assert_cookie("missing");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_cookie_value_when_matching() {
+        let server = new_server(new_app());
+
+        server
+            .get("/cookies")
+            .await
+            .assert_cookie_value("session", "abc123")
+            .assert_cookie_value("theme", "dark");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected cookie theme to be 'light'")]
+    async fn it_should_fail_assert_cookie_value_when_different() {
+        let server = new_server(new_app());
+
+        server
+            .get("/cookies")
+            .await
+            .assert_cookie_value("theme", "light");
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_fail_assert_cookie_value_when_missing() {
+        let server = new_server(new_app());
+
+        server
+            .get("/cookies")
+            .await
+            .assert_cookie_value("missing", "abc123");
+    }
+}
+
+#[cfg(test)]
+mod test_request_cookies {
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::cookie::Cookie;
+    use ::cookie::CookieJar;
+
+    use crate::test_utils::get_cookie_header;
+    use crate::test_utils::new_server;
+
+    fn new_app() -> Router {
+        Router::new().route("/cookies", get(get_cookie_header))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_cookies_added_in_bulk() {
+        let server = new_server(new_app());
+
+        let mut cookies = CookieJar::new();
+        cookies.add(Cookie::new("first", "1"));
+        cookies.add(Cookie::new("second", "2"));
+
+        let text = server.get("/cookies").add_cookies(cookies).await.text();
+
+        assert_eq!(text, "first=1; second=2");
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_cookies_with_same_name_when_added_in_bulk() {
+        let server = new_server(new_app());
+
+        let mut cookies = CookieJar::new();
+        cookies.add(Cookie::new("first", "replaced"));
+
+        let text = server
+            .get("/cookies")
+            .add_cookie(Cookie::new("first", "1"))
+            .add_cookies(cookies)
+            .await
+            .text();
+
+        assert_eq!(text, "first=replaced");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_cookie_removed_from_request() {
+        let server = new_server(new_app());
+
+        let text = server
+            .get("/cookies")
+            .add_cookie(Cookie::new("first", "1"))
+            .add_cookie(Cookie::new("second", "2"))
+            .remove_cookie("first")
+            .await
+            .text();
+
+        assert_eq!(text, "second=2");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_cookie_removed_from_server() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("session", "abc123"));
+        server.add_cookie(Cookie::new("theme", "dark"));
+
+        server.remove_cookie("session");
+        let text = server.get("/cookies").await.text();
+
+        assert_eq!(text, "theme=dark");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_only_cookies_in_jar_given() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("session", "abc123"));
+
+        let mut cookies = CookieJar::new();
+        cookies.add(Cookie::new("first", "1"));
+        cookies.add(Cookie::new("second", "2"));
+
+        let text = server
+            .get("/cookies")
+            .add_cookie(Cookie::new("theme", "dark"))
+            .with_cookie_jar(cookies)
+            .await
+            .text();
+
+        assert_eq!(text, "first=1; second=2");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_cookies_added_after_jar_given() {
+        let server = new_server(new_app());
+
+        let mut cookies = CookieJar::new();
+        cookies.add(Cookie::new("first", "1"));
+
+        let text = server
+            .get("/cookies")
+            .with_cookie_jar(cookies)
+            .add_cookie(Cookie::new("second", "2"))
+            .await
+            .text();
+
+        assert_eq!(text, "first=1; second=2");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_server_cookies_with_no_cookies() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("session", "abc123"));
+
+        let text = server.get("/cookies").no_cookies().await.text();
+
+        assert_eq!(text, "");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_request_cookies_with_no_cookies() {
+        let server = new_server(new_app());
+
+        let text = server
+            .get("/cookies")
+            .no_cookies()
+            .add_cookie(Cookie::new("session", "abc123"))
+            .await
+            .text();
+
+        assert_eq!(text, "");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_server_cookies_on_later_requests_after_no_cookies() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("session", "abc123"));
+
+        server.get("/cookies").no_cookies().await;
+        let text = server.get("/cookies").await.text();
+
+        assert_eq!(text, "session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_ignore_removing_cookie_which_does_not_exist() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("theme", "dark"));
+
+        server.remove_cookie("session");
+        let text = server.get("/cookies").await.text();
+
+        assert_eq!(text, "theme=dark");
+    }
+}
+
+#[cfg(test)]
+mod test_follow_redirects {
+    use super::*;
+
+    use ::axum::http::header::AUTHORIZATION;
+    use ::axum::http::header::COOKIE;
+    use ::axum::http::header::LOCATION;
+    use ::axum::http::header::SET_COOKIE;
+    use ::axum::http::HeaderMap;
+    use ::axum::http::Method;
+    use ::axum::http::StatusCode;
+    use ::axum::response::IntoResponse;
+    use ::axum::response::Response;
+    use ::axum::routing::any;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::cookie::Cookie;
+
+    use crate::test_utils::new_server;
+
+    async fn redirect_relative() -> (StatusCode, [(&'static str, &'static str); 1]) {
+        (StatusCode::FOUND, [(LOCATION.as_str(), "destination")])
+    }
+
+    async fn redirect_absolute() -> (StatusCode, [(&'static str, &'static str); 1]) {
+        (
+            StatusCode::FOUND,
+            [(LOCATION.as_str(), "/redirect/destination")],
+        )
+    }
+
+    async fn redirect_see_other() -> (StatusCode, [(&'static str, &'static str); 1]) {
+        (StatusCode::SEE_OTHER, [(LOCATION.as_str(), "/method")])
+    }
+
+    async fn redirect_loop() -> (StatusCode, [(&'static str, &'static str); 1]) {
+        (StatusCode::FOUND, [(LOCATION.as_str(), "/loop")])
+    }
+
+    async fn redirect_with_cookie() -> (StatusCode, [(&'static str, &'static str); 2]) {
+        (
+            StatusCode::FOUND,
+            [
+                (LOCATION.as_str(), "/cookie"),
+                (SET_COOKIE.as_str(), "session=abc123"),
+            ],
+        )
+    }
+
+    /// Sends the user to log in, and back here, when they have no session.
+    async fn get_account(headers: HeaderMap) -> Response {
+        match headers.get(COOKIE) {
+            Some(_) => "welcome back".into_response(),
+            None => (StatusCode::FOUND, [(LOCATION.as_str(), "/account/login")]).into_response(),
+        }
+    }
+
+    async fn redirect_account_login() -> (StatusCode, [(&'static str, &'static str); 2]) {
+        (
+            StatusCode::FOUND,
+            [
+                (LOCATION.as_str(), "/account"),
+                (SET_COOKIE.as_str(), "session=abc123"),
+            ],
+        )
+    }
+
+    async fn get_destination() -> &'static str {
+        "destination"
+    }
+
+    async fn any_method(method: Method) -> String {
+        method.to_string()
+    }
+
+    async fn redirect_credentials() -> (StatusCode, [(&'static str, &'static str); 1]) {
+        (StatusCode::FOUND, [(LOCATION.as_str(), "/credentials")])
+    }
+
+    async fn redirect_to(location: String) -> (StatusCode, [(&'static str, String); 1]) {
+        (StatusCode::FOUND, [(LOCATION.as_str(), location)])
+    }
+
+    async fn get_cookie(headers: HeaderMap) -> String {
+        headers
+            .get(COOKIE)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "cookie-not-found".to_string())
+    }
+
+    async fn get_credentials(headers: HeaderMap) -> String {
+        let authorization = headers
+            .get(AUTHORIZATION)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "authorization-not-found".to_string());
+        let cookie = get_cookie(headers).await;
+
+        format!("{}, {}", authorization, cookie)
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/redirect/relative", get(redirect_relative))
+            .route("/redirect/absolute", get(redirect_absolute))
+            .route("/redirect/destination", get(get_destination))
+            .route("/redirect/credentials", get(redirect_credentials))
+            .route("/see-other", post(redirect_see_other))
+            .route("/method", any(any_method))
+            .route("/loop", get(redirect_loop))
+            .route("/account", get(get_account))
+            .route("/account/login", get(redirect_account_login))
+            .route("/login", get(redirect_with_cookie))
+            .route("/cookie", get(get_cookie))
+            .route("/credentials", get(get_credentials))
+    }
+
+    /// Returns a server which redirects to `/credentials` on a different server.
+    fn new_server_redirecting_to(other_server: &Server) -> Server {
+        let location = format!("{}/credentials", other_server.base_url());
+        let app = Router::new().route(
+            "/redirect/other",
+            get(move || redirect_to(location.clone())),
+        );
+
+        new_server(app)
+    }
+
+    #[tokio::test]
+    async fn it_should_not_follow_redirects_by_default() {
+        let server = new_server(new_app());
+
+        server
+            .get("/redirect/absolute")
+            .await
+            .assert_status(StatusCode::FOUND);
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_location_when_matching() {
+        let server = new_server(new_app());
+
+        server
+            .get("/redirect/absolute")
+            .await
+            .assert_location("/redirect/destination");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_location_for_relative_and_absolute_forms() {
+        let server = new_server(new_app());
+        let full_location = format!(
+            "http://{}/redirect/destination",
+            server.server_address().authority().unwrap()
+        );
+
+        server
+            .get("/redirect/relative")
+            .await
+            .assert_location("/redirect/destination")
+            .assert_location(&full_location);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected location '/other'")]
+    async fn it_should_fail_assert_location_when_different() {
+        let server = new_server(new_app());
+
+        server
+            .get("/redirect/absolute")
+            .await
+            .assert_location("/other");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected a redirect status code")]
+    async fn it_should_fail_assert_location_when_not_a_redirect() {
+        let server = new_server(new_app());
+
+        server
+            .get("/redirect/destination")
+            .await
+            .assert_location("/redirect/destination");
+    }
+
+    #[tokio::test]
+    async fn it_should_follow_absolute_path_redirect() {
+        let server = new_server(new_app());
+        let response = server.get("/redirect/absolute").follow_redirects(5).await;
+
+        assert_eq!(response.text(), "destination");
+        assert_eq!(response.request_uri().path(), "/redirect/destination");
+    }
+
+    #[tokio::test]
+    async fn it_should_follow_relative_path_redirect() {
+        let server = new_server(new_app());
+        let response = server.get("/redirect/relative").follow_redirects(5).await;
+
+        assert_eq!(response.text(), "destination");
+    }
+
+    #[tokio::test]
+    async fn it_should_change_post_to_get_on_see_other() {
+        let server = new_server(new_app());
+        let text = server
+            .post("/see-other")
+            .text("body")
+            .follow_redirects(5)
+            .await
+            .text();
+
+        assert_eq!(text, "GET");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_cookies_set_during_redirect() {
+        let server = new_server(new_app());
+        let text = server.get("/login").follow_redirects(5).await.text();
+
+        assert_eq!(text, "session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_redirect_when_max_redirects_is_reached() {
+        let server = new_server(new_app());
+
+        server
+            .get("/redirect/relative")
+            .follow_redirects(0)
+            .await
+            .assert_status(StatusCode::FOUND);
+    }
+
+    #[tokio::test]
+    async fn it_should_stop_redirect_loop_at_max_redirects() {
+        let server = new_server(new_app());
+
+        server
+            .get("/loop")
+            .follow_redirects(5)
+            .await
+            .assert_status(StatusCode::FOUND);
+    }
+
+    #[tokio::test]
+    async fn it_should_follow_redirect_back_to_path_already_requested() {
+        let server = new_server(new_app());
+        let response = server.get("/account").follow_redirects(5).await;
+
+        assert_eq!(response.text(), "welcome back");
+        assert_eq!(response.request_uri().path(), "/account");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_credentials_when_redirected_to_same_host() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/redirect/credentials")
+            .bearer_token("my-token")
+            .add_cookie(Cookie::new("session", "abc123"))
+            .follow_redirects(5)
+            .await
+            .text();
+
+        assert_eq!(text, "Bearer my-token, session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_credentials_when_redirected_to_different_host() {
+        let other_server = new_server(new_app());
+        let server = new_server_redirecting_to(&other_server);
+        let text = server
+            .get("/redirect/other")
+            .bearer_token("my-token")
+            .add_cookie(Cookie::new("session", "abc123"))
+            .follow_redirects(5)
+            .await
+            .text();
+
+        assert_eq!(text, "authorization-not-found, cookie-not-found");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_cookies_with_matching_domain_when_redirected_to_different_host() {
+        let other_server = new_server(new_app());
+        let server = new_server_redirecting_to(&other_server);
+        let cookie = Cookie::build("session", "abc123")
+            .domain("127.0.0.1")
+            .finish();
+        let text = server
+            .get("/redirect/other")
+            .add_cookie(cookie)
+            .follow_redirects(5)
+            .await
+            .text();
+
+        assert_eq!(text, "authorization-not-found, session=abc123");
+    }
+}
+
+#[cfg(test)]
+mod test_decompress {
+    use ::axum::http::header::ACCEPT_ENCODING;
+    use ::axum::http::header::CONTENT_ENCODING;
+    use ::axum::http::HeaderMap;
+    use ::axum::http::StatusCode;
+    use ::axum::response::IntoResponse;
+    use ::axum::response::Response;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::flate2::write::GzEncoder;
+    use ::flate2::write::ZlibEncoder;
+    use ::flate2::Compression;
+    use ::std::io::Write;
+
+    use crate::test_utils::new_server;
+
+    const TEXT: &str = "Hello, this is compressed!";
+
+    async fn get_gzip() -> ([(&'static str, &'static str); 1], Vec<u8>) {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(TEXT.as_bytes()).unwrap();
+
+        (
+            [(CONTENT_ENCODING.as_str(), "gzip")],
+            encoder.finish().unwrap(),
+        )
+    }
+
+    async fn get_deflate() -> ([(&'static str, &'static str); 1], Vec<u8>) {
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(TEXT.as_bytes()).unwrap();
+
+        (
+            [(CONTENT_ENCODING.as_str(), "deflate")],
+            encoder.finish().unwrap(),
+        )
+    }
+
+    async fn get_brotli() -> ([(&'static str, &'static str); 1], Vec<u8>) {
+        let mut compressed = vec![];
+        {
+            let mut encoder = ::brotli::CompressorWriter::new(&mut compressed, 4096, 5, 22);
+            encoder.write_all(TEXT.as_bytes()).unwrap();
+        }
+
+        ([(CONTENT_ENCODING.as_str(), "br")], compressed)
+    }
+
+    async fn get_large_gzip() -> ([(&'static str, &'static str); 1], Vec<u8>) {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(&[b'a'; 2048]).unwrap();
+
+        (
+            [(CONTENT_ENCODING.as_str(), "gzip")],
+            encoder.finish().unwrap(),
+        )
+    }
+
+    async fn get_gzip_no_content() -> (StatusCode, [(&'static str, &'static str); 1]) {
+        (
+            StatusCode::NO_CONTENT,
+            [(CONTENT_ENCODING.as_str(), "gzip")],
+        )
+    }
+
+    async fn get_unknown_encoding() -> ([(&'static str, &'static str); 1], &'static str) {
+        ([(CONTENT_ENCODING.as_str(), "zstd")], TEXT)
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/gzip", get(get_gzip))
+            .route("/deflate", get(get_deflate))
+            .route("/br", get(get_brotli))
+            .route("/gzip/large", get(get_large_gzip))
+            .route("/gzip/no-content", get(get_gzip_no_content))
+            .route("/unknown-encoding", get(get_unknown_encoding))
+            .route("/negotiated", get(get_negotiated))
+            .route("/accept-encoding", get(get_accept_encoding))
+    }
+
+    /// Only compresses the response when the client asks for gzip.
+    async fn get_negotiated(headers: HeaderMap) -> Response {
+        let accepts_gzip = headers
+            .get(ACCEPT_ENCODING)
+            .map(|value| value.to_str().unwrap().contains("gzip"))
+            .unwrap_or(false);
+
+        if accepts_gzip {
+            get_gzip().await.into_response()
+        } else {
+            TEXT.into_response()
+        }
+    }
+
+    async fn get_accept_encoding(headers: HeaderMap) -> String {
+        headers
+            .get(ACCEPT_ENCODING)
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn it_should_decompress_by_default() {
+        let server = new_server(new_app());
+        let text = server.get("/gzip").await.text();
+
+        assert_eq!(text, TEXT);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_decompress_with_no_decompress() {
+        let server = new_server(new_app());
+        let response = server.get("/gzip").no_decompress().await;
+
+        assert_ne!(response.as_bytes(), TEXT.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn it_should_send_accept_encoding_by_default() {
+        let server = new_server(new_app());
+
+        server
+            .get("/accept-encoding")
+            .await
+            .assert_text("gzip, deflate, br");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_replace_accept_encoding_already_set() {
+        let server = new_server(new_app());
+
+        server
+            .get("/accept-encoding")
+            .add_header(ACCEPT_ENCODING, "identity")
+            .await
+            .assert_text("identity");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_accept_encoding_with_no_decompress() {
+        let server = new_server(new_app());
+
+        server
+            .get("/accept-encoding")
+            .no_decompress()
+            .await
+            .assert_text("");
+    }
+
+    #[tokio::test]
+    async fn it_should_transparently_decode_negotiated_compression() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/negotiated")
+            .await
+            .assert_header_missing(CONTENT_ENCODING)
+            .text();
+
+        assert_eq!(text, TEXT);
+    }
+
+    #[tokio::test]
+    async fn it_should_receive_compressed_bytes_with_no_decompress() {
+        let server = new_server(new_app());
+        let response = server
+            .get("/negotiated")
+            .add_header(ACCEPT_ENCODING, "gzip")
+            .no_decompress()
+            .await;
+
+        assert_ne!(response.as_bytes(), TEXT.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn it_should_decompress_gzip() {
+        let server = new_server(new_app());
+        let text = server.get("/gzip").decompress(true).await.text();
+
+        assert_eq!(text, TEXT);
+    }
+
+    #[tokio::test]
+    async fn it_should_decompress_deflate() {
+        let server = new_server(new_app());
+        let text = server.get("/deflate").decompress(true).await.text();
+
+        assert_eq!(text, TEXT);
+    }
+
+    #[tokio::test]
+    async fn it_should_decompress_brotli() {
+        let server = new_server(new_app());
+        let text = server.get("/br").decompress(true).await.text();
+
+        assert_eq!(text, TEXT);
+    }
+
+    #[tokio::test]
+    async fn it_should_set_content_length_of_decompressed_body() {
+        let server = new_server(new_app());
+        let response = server.get("/gzip").await;
+
+        assert_eq!(response.content_length(), Some(TEXT.len() as u64));
+    }
+
+    #[tokio::test]
+    async fn it_should_error_when_decompressed_body_is_over_max_size() {
+        let server = new_server(new_app());
+        let error = server
+            .get("/gzip/large")
+            .max_response_size(1024)
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(format!("{:?}", error).contains("larger than the maximum size of 1024 bytes"));
+    }
+
+    #[tokio::test]
+    async fn it_should_read_decompressed_body_within_max_size() {
+        let server = new_server(new_app());
+        let response = server.get("/gzip/large").max_response_size(2048).await;
+
+        assert_eq!(response.as_bytes().len(), 2048);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_decompress_head_requests() {
+        let server = new_server(new_app());
+
+        server
+            .head("/gzip")
+            .await
+            .assert_status_ok()
+            .assert_header(CONTENT_ENCODING, "gzip");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_decompress_empty_bodies() {
+        let server = new_server(new_app());
+
+        server
+            .get("/gzip/no-content")
+            .await
+            .assert_status(StatusCode::NO_CONTENT)
+            .assert_text("");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_unknown_encodings_unchanged() {
+        let server = new_server(new_app());
+
+        server
+            .get("/unknown-encoding")
+            .await
+            .assert_header(CONTENT_ENCODING, "zstd")
+            .assert_text(TEXT);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_decompress_when_accept_encoding_already_set() {
+        let server = new_server(new_app());
+        let response = server
+            .get("/negotiated")
+            .add_header(ACCEPT_ENCODING, "gzip")
+            .await;
+
+        assert_ne!(response.as_bytes(), TEXT.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn it_should_decompress_when_accept_encoding_already_set_with_decompress() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/negotiated")
+            .add_header(ACCEPT_ENCODING, "gzip")
+            .decompress(true)
+            .await
+            .text();
+
+        assert_eq!(text, TEXT);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test_unix_socket {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::hyper_tls::HttpsConnector;
+    use ::hyperlocal::UnixServerExt;
+    use ::std::env::temp_dir;
+    use ::std::fs::remove_file;
+    use ::std::process;
+
+    use crate::test_utils::get_ping;
+
+    #[tokio::test]
+    async fn it_should_send_requests_over_unix_socket() {
+        let socket_path = temp_dir().join(format!("kantan-test-{}.sock", process::id()));
+        let _ = remove_file(&socket_path);
+
+        // Run the app on the socket.
+        let app = Router::new().route("/ping", get(get_ping));
+        let app_server = ::hyper::Server::bind_unix(&socket_path)
+            .expect("Should bind to unix socket")
+            .serve(app.into_make_service());
+        let app_thread = ::tokio::spawn(async move {
+            app_server.await.expect("Expect server to start serving");
+        });
+
+        // Get the request.
+        let server = Server::new_unix(&socket_path).expect("Should create server");
+        let text = server.get("/ping").expect_success().await.text();
+
+        app_thread.abort();
+        let _ = remove_file(&socket_path);
+
+        assert_eq!(text, "pong!");
+    }
+
+    #[test]
+    #[should_panic(expected = "sends requests over a Unix domain socket")]
+    fn it_should_panic_when_setting_tcp_client() {
+        let socket_path = temp_dir().join(format!("kantan-test-client-{}.sock", process::id()));
+
+        let _ = Server::new_unix(&socket_path)
+            .expect("Should create server")
+            .with_client(::hyper::Client::builder(), HttpsConnector::new());
+    }
+}
+
+#[cfg(test)]
+mod test_try_json {
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::serde::ser::Error as SerError;
+    use ::serde::Serialize;
+    use ::serde::Serializer;
+
+    use crate::test_utils::new_server;
+    use crate::test_utils::post_echo;
+
+    struct Unserializable;
+
+    impl Serialize for Unserializable {
+        fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            Err(S::Error::custom("cannot serialize"))
+        }
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/echo", post(post_echo))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_json_when_serializable() {
+        let server = new_server(new_app());
+        let text = server
+            .post("/echo")
+            .try_json(&vec![1, 2, 3])
+            .expect("Should serialize")
+            .await
+            .text();
+
+        assert_eq!(text, "[1,2,3]");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_error_when_not_serializable() {
+        let server = new_server(new_app());
+        let result = server.post("/echo").try_json(&Unserializable);
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_on_json_when_not_serializable() {
+        let server = new_server(new_app());
+        let _ = server.post("/echo").json(&Unserializable);
+    }
+}
+
+#[cfg(test)]
+mod test_accept_and_user_agent {
+    use ::axum::http::header::ACCEPT;
+    use ::axum::http::header::USER_AGENT;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_accept(headers: HeaderMap) -> String {
+        match headers.get(ACCEPT).map(|h| h.to_str().unwrap()) {
+            Some("application/json") => r#"{"format":"json"}"#.to_string(),
+            Some(other) => format!("unsupported {}", other),
+            None => "header-not-found".to_string(),
+        }
+    }
+
+    async fn get_user_agent(headers: HeaderMap) -> String {
+        headers
+            .get(USER_AGENT)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "header-not-found".to_string())
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/accept", get(get_accept))
+            .route("/user-agent", get(get_user_agent))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_accept_header() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/accept")
+            .accept("application/json")
+            .await
+            .text();
+
+        assert_eq!(text, r#"{"format":"json"}"#);
+    }
+
+    #[tokio::test]
+    async fn it_should_send_other_accept_header() {
+        let server = new_server(new_app());
+        let text = server.get("/accept").accept("text/html").await.text();
+
+        assert_eq!(text, "unsupported text/html");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_user_agent_header() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/user-agent")
+            .user_agent("my-test-agent/1.0")
+            .await
+            .text();
+
+        assert_eq!(text, "my-test-agent/1.0");
+    }
+}
+
+#[cfg(test)]
+mod test_stream_body {
+    use ::anyhow::anyhow;
+    use ::axum::body::Bytes;
+    use ::axum::http::header::TRANSFER_ENCODING;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::futures_util::stream;
+
+    use crate::test_utils::new_server;
+    use crate::test_utils::post_echo;
+
+    async fn post_stream_info(headers: HeaderMap, body: Bytes) -> String {
+        let transfer_encoding = headers
+            .get(TRANSFER_ENCODING)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "none".to_string());
+
+        format!("{} {}", transfer_encoding, body.len())
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/stream-info", post(post_stream_info))
+            .route("/echo", post(post_echo))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_all_chunks_of_the_stream() {
+        let server = new_server(new_app());
+        let chunks = (0..3).map(|i| Ok(Bytes::from(format!("chunk-{} ", i))));
+        let text = server
+            .post("/echo")
+            .stream_body(stream::iter(chunks))
+            .await
+            .text();
+
+        assert_eq!(text, "chunk-0 chunk-1 chunk-2 ");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_stream_using_chunked_transfer_encoding() {
+        let server = new_server(new_app());
+        let chunks = (0..1024).map(|_| Ok(Bytes::from(vec![b'a'; 1024])));
+        let text = server
+            .post("/stream-info")
+            .stream_body(stream::iter(chunks))
+            .await
+            .text();
+
+        assert_eq!(text, "chunked 1048576");
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_if_the_stream_errors() {
+        let server = new_server(new_app());
+        let chunks = vec![
+            Ok(Bytes::from("first")),
+            Err(anyhow!("failed to read chunk")),
+        ];
+
+        server.post("/echo").stream_body(stream::iter(chunks)).await;
+    }
+}
+
+#[cfg(test)]
+mod test_server_clone {
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::axum_extra::extract::cookie::Cookie as AxumCookie;
+    use ::axum_extra::extract::cookie::CookieJar;
+
+    use crate::test_utils::new_server;
+
+    const SESSION_COOKIE_NAME: &str = "session";
+
+    async fn post_login(cookies: CookieJar) -> (CookieJar, &'static str) {
+        let cookies = cookies.add(AxumCookie::new(SESSION_COOKIE_NAME, "my-session"));
+
+        (cookies, "logged in")
+    }
+
+    async fn get_session(cookies: CookieJar) -> String {
+        cookies
+            .get(SESSION_COOKIE_NAME)
+            .map(|c| c.value().to_string())
+            .unwrap_or_else(|| "not-logged-in".to_string())
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/login", post(post_login))
+            .route("/session", get(get_session))
+    }
+
+    #[tokio::test]
+    async fn it_should_share_saved_cookies_between_clones() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+        let other_server = server.clone();
+
+        server.post("/login").await;
+        let text = other_server.get("/session").await.text();
+
+        assert_eq!(text, "my-session");
+    }
+
+    #[tokio::test]
+    async fn it_should_share_cleared_cookies_between_clones() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+        let mut other_server = server.clone();
+
+        server.post("/login").await;
+        other_server.clear_cookies();
+        let text = server.get("/session").await.text();
+
+        assert_eq!(text, "not-logged-in");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_change_original_when_changing_clone() {
+        let server = new_server(new_app());
+        let other_server = server.clone().with_base_path("/v2");
+
+        other_server
+            .get("/session")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        let text = server.get("/session").await.text();
+
+        assert_eq!(text, "not-logged-in");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_share_saving_cookies_between_clones() {
+        let server = new_server(new_app());
+        let mut other_server = server.clone();
+        other_server.save_cookies();
+
+        server.post("/login").await;
+        let text = other_server.get("/session").await.text();
+
+        assert_eq!(text, "not-logged-in");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_the_server_running_while_a_clone_is_alive() {
+        let server = new_server(new_app());
+        let other_server = server.clone();
+        ::std::mem::drop(server);
+
+        let text = other_server.get("/session").await.text();
+
+        assert_eq!(text, "not-logged-in");
+    }
+}
+
+#[cfg(test)]
+mod test_http_version {
+    use ::axum::http::Version;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::get_version;
+    use crate::test_utils::new_server;
+
+    fn new_app() -> Router {
+        Router::new().route("/version", get(get_version))
+    }
+
+    #[tokio::test]
+    async fn it_should_use_http1_by_default() {
+        let server = new_server(new_app());
+        let text = server.get("/version").await.text();
+
+        assert_eq!(text, "HTTP/1.1");
+    }
+
+    #[tokio::test]
+    async fn it_should_use_http_version_given() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/version")
+            .http_version(Version::HTTP_10)
+            .await
+            .text();
+
+        assert_eq!(text, "HTTP/1.0");
+    }
+
+    #[tokio::test]
+    async fn it_should_use_http2_when_using_prior_knowledge() {
+        let server = new_server(new_app());
+        let response = server.get("/version").http2_prior_knowledge().await;
+
+        assert_eq!(response.text(), "HTTP/2.0");
+
+        let (_, parts, _) = response.into_parts();
+        assert_eq!(parts.version, Version::HTTP_2);
+    }
+}
+
+#[cfg(test)]
+mod test_server_address {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::get_ping;
+    use crate::test_utils::new_server;
+
+    fn new_app() -> Router {
+        Router::new().route("/ping", get(get_ping))
+    }
+
+    #[tokio::test]
+    async fn it_should_return_address_with_bound_port() {
+        let server = new_server(new_app());
+        let address = server.server_address();
+
+        assert_eq!(address.scheme_str(), Some("http"));
+        assert_eq!(address.host(), Some("127.0.0.1"));
+        assert_ne!(address.port_u16(), None);
+        assert_ne!(address.port_u16(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_a_second_server_to_use_the_address() {
+        let server = new_server(new_app());
+        let address = server.server_address();
+
+        let other_server = Server::new(address.to_string()).expect("Should create server");
+        let text = other_server.get("/ping").await.text();
+
+        assert_eq!(text, "pong!");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_address_given() {
+        let server =
+            Server::new("http://example.com:8080".to_string()).expect("Should create server");
+        let address = server.server_address();
+
+        assert_eq!(address.to_string(), "http://example.com:8080/");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_bound_port() {
+        let server = new_server(new_app());
+
+        assert_eq!(Some(server.port()), server.server_address().port_u16());
+    }
+
+    #[tokio::test]
+    async fn it_should_return_default_port_for_scheme() {
+        let http_server =
+            Server::new("http://example.com".to_string()).expect("Should create server");
+        let https_server =
+            Server::new("https://example.com".to_string()).expect("Should create server");
+
+        assert_eq!(http_server.port(), 80);
+        assert_eq!(https_server.port(), 443);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_base_url_without_trailing_slash() {
+        let server = new_server(new_app());
+        let base_url = server.base_url();
+
+        assert_eq!(base_url, format!("http://127.0.0.1:{}", server.port()));
+    }
+
+    #[tokio::test]
+    async fn it_should_return_base_url_with_path_and_default_scheme() {
+        let server = Server::new("example.com:8080".to_string()).expect("Should create server");
+        let api_server =
+            Server::new("https://example.com/api/".to_string()).expect("Should create server");
+
+        assert_eq!(server.base_url(), "http://example.com:8080");
+        assert_eq!(api_server.base_url(), "https://example.com/api");
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_requests_to_base_url() {
+        let server = new_server(new_app());
+        let other_server = Server::new(server.base_url()).expect("Should create server");
+
+        other_server.get("/ping").await.assert_text("pong!");
+    }
+}
+
+#[cfg(test)]
+mod test_send {
+    use super::*;
+
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::net::TcpListener;
+
+    use crate::test_utils::get_ping;
+    use crate::test_utils::new_server;
+
+    async fn get_not_found() -> StatusCode {
+        StatusCode::NOT_FOUND
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/ping", get(get_ping))
+            .route("/not-found", get(get_not_found))
+    }
+
+    /// Returns the address of a port that nothing is listening on.
+    fn new_closed_server_address() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind to a free port");
+        let socket_address = listener.local_addr().expect("Should get the bound address");
+
+        format!("http://{}", socket_address)
+    }
+
+    #[tokio::test]
+    async fn it_should_return_response_on_success() {
+        let server = new_server(new_app());
+        let response = server
+            .get("/ping")
+            .send()
+            .await
+            .expect("Should send request");
+
+        assert_eq!(response.text(), "pong!");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_error_when_connection_refused() {
+        let server = Server::new(new_closed_server_address()).expect("Should create server");
+        let result = server.get("/ping").send().await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_not_apply_expected_status() {
+        let server = new_server(new_app());
+        let response = server
+            .get("/not-found")
+            .expect_success()
+            .send()
+            .await
+            .expect("Should send request");
+
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    }
+}
+
+#[cfg(test)]
+mod test_content_length {
+    use ::axum::body::Bytes;
+    use ::axum::http::header::CONTENT_LENGTH;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::futures_util::stream;
+
+    use crate::test_utils::new_server;
+
+    async fn get_content_length(headers: HeaderMap) -> String {
+        headers
+            .get(CONTENT_LENGTH)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "header-not-found".to_string())
+    }
+
+    async fn get_text() -> &'static str {
+        "hello!"
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/content-length", get(get_content_length))
+            .route("/content-length", post(get_content_length))
+            .route("/text", get(get_text))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_content_length_for_buffered_body() {
+        let server = new_server(new_app());
+        let text = server.post("/content-length").text("hello").await.text();
+
+        assert_eq!(text, "5");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_zero_content_length_for_empty_body() {
+        let server = new_server(new_app());
+        let text = server
+            .post("/content-length")
+            .bytes(Bytes::new())
+            .await
+            .text();
+
+        assert_eq!(text, "0");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_content_length_for_streamed_body() {
+        let server = new_server(new_app());
+        let chunks = vec![Ok(Bytes::from("hello"))];
+        let text = server
+            .post("/content-length")
+            .stream_body(stream::iter(chunks))
+            .await
+            .text();
+
+        assert_eq!(text, "header-not-found");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_content_length_header_given() {
+        let server = new_server(new_app());
+        let chunks = vec![Ok(Bytes::from("hello"))];
+        let text = server
+            .post("/content-length")
+            .add_header(CONTENT_LENGTH, "5")
+            .stream_body(stream::iter(chunks))
+            .await
+            .text();
+
+        assert_eq!(text, "5");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_content_length_of_response() {
+        let server = new_server(new_app());
+        let content_length = server.get("/text").await.content_length();
+
+        assert_eq!(content_length, Some(6));
+    }
+}
+
+#[cfg(test)]
+mod test_status_predicates {
+    use super::*;
+
+    use ::axum::extract::Path;
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::net::TcpListener;
+
+    use crate::test_utils::new_server;
+
+    async fn get_status(Path(status): Path<u16>) -> StatusCode {
+        StatusCode::from_u16(status).unwrap()
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/status/:status", get(get_status))
+    }
+
+    #[tokio::test]
+    async fn it_should_match_only_the_status_category() {
+        let server = new_server(new_app());
+
+        let response = server.get("/status/201").await;
+        assert!(response.is_success());
+        assert!(!response.is_informational());
+        assert!(!response.is_redirection());
+        assert!(!response.is_client_error());
+        assert!(!response.is_server_error());
+
+        let response = server.get("/status/307").await;
+        assert!(response.is_redirection());
+        assert!(!response.is_success());
+
+        let response = server.get("/status/404").await;
+        assert!(response.is_client_error());
+        assert!(!response.is_server_error());
+
+        let response = server.get("/status/503").await;
+        assert!(response.is_server_error());
+        assert!(!response.is_client_error());
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_status_in_range_when_within() {
+        let server = new_server(new_app());
+
+        server
+            .get("/status/200")
+            .await
+            .assert_status_in_range(200..=299);
+        server
+            .get("/status/299")
+            .await
+            .assert_status_in_range(200..=299);
+        server
+            .get("/status/404")
+            .await
+            .assert_status_in_range(404..=404);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected status code within 200..=299")]
+    async fn it_should_fail_assert_status_in_range_when_outside() {
+        let server = new_server(new_app());
+
+        server
+            .get("/status/404")
+            .await
+            .assert_status_in_range(200..=299);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_false_when_request_failed_to_reach_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind to a free port");
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        ::std::mem::drop(listener);
+
+        let server = Server::new(address).expect("Should create server");
+        let response = server.get("/status/200").expect_failure().await;
+
+        assert!(!response.is_success());
+        assert!(!response.is_server_error());
+    }
+}
+
+#[cfg(test)]
+mod test_server_cookies {
+    use ::axum::http::header::COOKIE;
+    use ::axum::http::header::SET_COOKIE;
+
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::cookie::Cookie;
+    use ::cookie::CookieJar;
+
+    use crate::test_utils::get_cookie_header;
+    use crate::test_utils::new_server;
+
+    async fn post_login() -> [(&'static str, &'static str); 1] {
+        [(SET_COOKIE.as_str(), "session=abc123; HttpOnly")]
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/cookies", get(get_cookie_header))
+            .route("/cookies", post(get_cookie_header))
+            .route("/login", post(post_login))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_seeded_cookie_on_every_request() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("csrf-token", "abc123"));
+
+        let first_text = server.get("/cookies").await.text();
+        let second_text = server.post("/cookies").await.text();
+
+        assert_eq!(first_text, "csrf-token=abc123");
+        assert_eq!(second_text, "csrf-token=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_seeded_cookies_on_every_request() {
+        let mut server = new_server(new_app());
+        let mut cookies = CookieJar::new();
+        cookies.add(Cookie::new("csrf-token", "abc123"));
+        cookies.add(Cookie::new("theme", "dark"));
+        server.add_cookies(cookies);
+
+        let first_text = server.get("/cookies").await.text();
+        let second_text = server.post("/cookies").await.text();
+
+        assert_eq!(first_text, "csrf-token=abc123; theme=dark");
+        assert_eq!(second_text, "csrf-token=abc123; theme=dark");
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_seeded_cookie_with_same_name() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("csrf-token", "abc123"));
+        server.add_cookie(Cookie::new("csrf-token", "xyz789"));
+
+        let text = server.get("/cookies").await.text();
+
+        assert_eq!(text, "csrf-token=xyz789");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_cookie_saved_from_response() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+
+        server.post("/login").await;
+        let cookie = server.cookie("session");
+
+        assert_eq!(cookie.value(), "abc123");
+        assert_eq!(cookie.http_only(), Some(true));
+    }
+
+    #[tokio::test]
+    async fn it_should_return_none_for_missing_cookie() {
+        let server = new_server(new_app());
+
+        assert!(server.maybe_cookie("session").is_none());
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_on_cookie_when_missing() {
+        let server = new_server(new_app());
+
+        let _ = server.cookie("session");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_all_cookies() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+        server.add_cookie(Cookie::new("theme", "dark"));
+
+        server.post("/login").await;
+        let cookies = server.cookies();
+
+        assert_eq!(cookies.get("session").unwrap().value(), "abc123");
+        assert_eq!(cookies.get("theme").unwrap().value(), "dark");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_cookies_after_clear_cookies() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+        server.post("/login").await;
+
+        server.clear_cookies();
+        let request = server
+            .get("/cookies")
+            .into_hyper_request()
+            .expect("Should build request");
+        let text = server.get("/cookies").await.text();
+
+        assert!(request.headers().get(COOKIE).is_none());
+        assert_eq!(text, "");
+    }
+
+    #[tokio::test]
+    async fn it_should_restore_cookies_from_snapshot() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+        server.post("/login").await;
+
+        let snapshot = server.snapshot_cookies();
+        server.clear_cookies();
+        server.add_cookie(Cookie::new("theme", "dark"));
+        server.restore_cookies(snapshot);
+
+        let text = server.get("/cookies").await.text();
+
+        assert_eq!(text, "session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_change_snapshot_when_server_cookies_change() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("theme", "dark"));
+
+        let snapshot = server.snapshot_cookies();
+        server.add_cookie(Cookie::new("theme", "light"));
+
+        assert_eq!(snapshot.get("theme").unwrap().value(), "dark");
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_request_to_override_seeded_cookie() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("csrf-token", "abc123"));
+
+        let text = server
+            .get("/cookies")
+            .add_cookie(Cookie::new("csrf-token", "overridden"))
+            .await
+            .text();
+
+        assert_eq!(text, "csrf-token=overridden");
+    }
+}
+
+#[cfg(test)]
+mod test_cookie_matching {
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::cookie::Cookie;
+
+    use crate::test_utils::get_cookie_header;
+    use crate::test_utils::new_server;
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/admin", get(get_cookie_header))
+            .route("/admin/users", get(get_cookie_header))
+            .route("/public", get(get_cookie_header))
+    }
+
+    fn new_admin_cookie() -> Cookie<'static> {
+        Cookie::build("admin-session", "abc123")
+            .path("/admin")
+            .finish()
+    }
+
+    #[tokio::test]
+    async fn it_should_send_cookie_to_matching_path() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/admin")
+            .add_cookie(new_admin_cookie())
+            .await
+            .text();
+
+        assert_eq!(text, "admin-session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_cookie_to_sub_path() {
+        let mut server = new_server(new_app());
+        server.add_cookie(new_admin_cookie());
+
+        let text = server.get("/admin/users").await.text();
+
+        assert_eq!(text, "admin-session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_cookie_to_other_path() {
+        let mut server = new_server(new_app());
+        server.add_cookie(new_admin_cookie());
+        server.add_cookie(Cookie::new("theme", "dark"));
+
+        let text = server.get("/public").await.text();
+
+        assert_eq!(text, "theme=dark");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_cookie_to_other_domain() {
+        let server = new_server(new_app());
+        let cookie = Cookie::build("session", "abc123")
+            .domain("example.com")
+            .finish();
+
+        let text = server.get("/public").add_cookie(cookie).await.text();
+
+        assert_eq!(text, "");
+    }
+}
+
+#[cfg(test)]
+mod test_cookie_expiry {
+    use ::axum::http::header::SET_COOKIE;
+
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::cookie::Cookie;
+    use ::std::time::Duration;
+    use ::tokio::time::sleep;
+
+    use crate::test_utils::get_cookie_header;
+    use crate::test_utils::new_server;
+
+    async fn post_login() -> [(&'static str, &'static str); 1] {
+        [(SET_COOKIE.as_str(), "session=abc123")]
+    }
+
+    async fn post_logout() -> [(&'static str, &'static str); 1] {
+        [(SET_COOKIE.as_str(), "session=; Max-Age=0")]
+    }
+
+    async fn post_login_briefly() -> [(&'static str, &'static str); 1] {
+        [(SET_COOKIE.as_str(), "session=abc123; Max-Age=1")]
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/cookies", get(get_cookie_header))
+            .route("/login", post(post_login))
+            .route("/login/briefly", post(post_login_briefly))
+            .route("/logout", post(post_logout))
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_expired_cookie() {
+        let server = new_server(new_app());
+        let cookie = Cookie::parse("session=abc123; Expires=Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .into_owned();
+
+        let text = server
+            .get("/cookies")
+            .add_cookie(cookie)
+            .add_cookie(Cookie::new("theme", "dark"))
+            .await
+            .text();
+
+        assert_eq!(text, "theme=dark");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_cookie_with_zero_max_age() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::parse("session=abc123; Max-Age=0").unwrap());
+
+        let text = server.get("/cookies").await.text();
+
+        assert_eq!(text, "");
+    }
+
+    #[tokio::test]
+    async fn it_should_prune_cookie_expired_by_server() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+
+        server.post("/login").await;
+        let logged_in_text = server.get("/cookies").await.text();
+        server.post("/logout").await;
+        let logged_out_text = server.get("/cookies").await.text();
+
+        assert_eq!(logged_in_text, "session=abc123");
+        assert_eq!(logged_out_text, "");
+    }
+
+    #[tokio::test]
+    async fn it_should_remove_cookie_deleted_by_server_from_jar() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+
+        server.post("/login").await;
+        assert!(server.maybe_cookie("session").is_some());
+
+        server.post("/logout").await;
+        assert!(server.maybe_cookie("session").is_none());
+        assert_eq!(server.cookies().iter().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_saved_cookie_once_max_age_has_passed() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+
+        server.post("/login/briefly").await;
+        let logged_in_text = server.get("/cookies").await.text();
+        sleep(Duration::from_millis(1100)).await;
+        let expired_text = server.get("/cookies").await.text();
+
+        assert_eq!(logged_in_text, "session=abc123");
+        assert_eq!(expired_text, "");
+    }
+
+    #[tokio::test]
+    async fn it_should_remove_cookie_deleted_with_past_expires_from_jar() {
+        let app = Router::new().route("/login", post(post_login)).route(
+            "/logout",
+            post(|| async {
+                [(
+                    SET_COOKIE.as_str(),
+                    "session=; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+                )]
+            }),
+        );
+        let mut server = new_server(app);
+        server.save_cookies();
+
+        server.post("/login").await;
+        server.post("/logout").await;
+
+        assert!(server.maybe_cookie("session").is_none());
+    }
+}
+
+#[cfg(test)]
+mod test_body {
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::hyper::Body;
+
+    use crate::test_utils::new_server;
+    use crate::test_utils::post_content_type;
+    use crate::test_utils::post_echo;
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/echo", post(post_echo))
+            .route("/content-type", post(post_content_type))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_body_given() {
+        let server = new_server(new_app());
+        let text = server.post("/echo").body(Body::from("hello!")).await.text();
+
+        assert_eq!(text, "hello!");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_set_content_type() {
+        let server = new_server(new_app());
+        let text = server
+            .post("/content-type")
+            .body(Body::from("hello!"))
+            .await
+            .text();
+
+        assert_eq!(text, "header-not-found");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_content_type_given() {
+        let server = new_server(new_app());
+        let text = server
+            .post("/content-type")
+            .content_type("application/xml")
+            .body(Body::from("<hello />"))
+            .await
+            .text();
+
+        assert_eq!(text, "application/xml");
+    }
+}
+
+#[cfg(test)]
+mod test_debug {
+    use ::axum::routing::post;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+    use crate::test_utils::post_echo;
+
+    fn new_app() -> Router {
+        Router::new().route("/echo", post(post_echo))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_body_when_debugging() {
+        let server = new_server(new_app());
+        let text = server
+            .post("/echo")
+            .debug(true)
+            .bearer_token("my-token")
+            .text("hello!")
+            .await
+            .text();
+
+        assert_eq!(text, "hello!");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_body_when_debugging_unredacted() {
+        let server = new_server(new_app());
+        let text = server
+            .post("/echo")
+            .debug_unredacted()
+            .text("hello!")
+            .await
+            .text();
+
+        assert_eq!(text, "hello!");
+    }
+}
+
+#[cfg(test)]
+mod test_host {
+    use ::axum::http::header::HOST;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_host(headers: HeaderMap) -> String {
+        let hosts: Vec<&str> = headers
+            .get_all(HOST)
+            .iter()
+            .map(|h| h.to_str().unwrap())
+            .collect();
+
+        hosts.join(", ")
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/host", get(get_host))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_host_from_server_address_by_default() {
+        let server = new_server(new_app());
+        let expected = server.server_address().authority().unwrap().to_string();
+        let text = server.get("/host").await.text();
+
+        assert_eq!(text, expected);
+    }
+
+    #[tokio::test]
+    async fn it_should_send_host_given() {
+        let server = new_server(new_app());
+        let text = server.get("/host").host("example.com").await.text();
+
+        assert_eq!(text, "example.com");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_host_with_no_host_header() {
+        let server = new_server(new_app());
+
+        server
+            .get("/host")
+            .no_host_header()
+            .await
+            .assert_status_ok()
+            .assert_text("");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_host_given_with_no_host_header() {
+        let server = new_server(new_app());
+
+        server
+            .get("/host")
+            .no_host_header()
+            .host("example.com")
+            .await
+            .assert_text("example.com");
+    }
+
+    #[tokio::test]
+    async fn it_should_use_http2_with_no_host_header() {
+        let server = new_server(new_app());
+        let response = server
+            .get("/host")
+            .no_host_header()
+            .http2_prior_knowledge()
+            .await;
+        let (_, parts, _) = response.into_parts();
+
+        assert_eq!(parts.version, ::axum::http::Version::HTTP_2);
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_host_when_given_twice() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/host")
+            .host("example.com")
+            .host("example.org:8080")
+            .await
+            .text();
+
+        assert_eq!(text, "example.org:8080");
+    }
+}
+
+#[cfg(test)]
+mod test_with_client {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::hyper::Client;
+    use ::hyper_tls::HttpsConnector;
+
+    use crate::test_utils::get_version;
+    use crate::test_utils::new_server;
+
+    fn new_server_with_http2_client() -> Server {
+        let app = Router::new().route("/version", get(get_version));
+        let mut builder = Client::builder();
+        builder.http2_only(true);
+
+        new_server(app).with_client(builder, HttpsConnector::new())
+    }
+
+    #[tokio::test]
+    async fn it_should_send_requests_using_client_given() {
+        let server = new_server_with_http2_client();
+        let text = server.get("/version").await.text();
+
+        assert_eq!(text, "HTTP/2.0");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_client_after_creating_requests() {
+        let server = new_server_with_http2_client();
+
+        server.get("/version").await;
+        let text = server.get("/version").await.text();
+
+        assert_eq!(text, "HTTP/2.0");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_client_given_with_no_host_header() {
+        let server = new_server_with_http2_client();
+        let text = server.get("/version").no_host_header().await.text();
+
+        assert_eq!(text, "HTTP/2.0");
+    }
+}
+
+#[cfg(test)]
+mod test_append_path {
+    use ::axum::Router;
+
+    use crate::test_utils::get_path;
+    use crate::test_utils::new_server;
+
+    fn new_app() -> Router {
+        Router::new().fallback(get_path)
+    }
+
+    #[tokio::test]
+    async fn it_should_append_segments_onto_path() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/users")
+            .append_path("123")
+            .append_path("posts")
+            .await
+            .text();
+
+        assert_eq!(text, "/users/123/posts");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_duplicate_slashes() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/users/")
+            .append_path("/123/")
+            .append_path("//posts")
+            .await
+            .text();
+
+        assert_eq!(text, "/users/123/posts");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_existing_query() {
+        let server = new_server(new_app());
+        let text = server
+            .get("/users?page=2")
+            .query_param("sort", "name")
+            .append_path("123")
+            .await
+            .text();
+
+        assert_eq!(text, "/users/123?page=2&sort=name");
+    }
+
+    #[tokio::test]
+    async fn it_should_append_onto_base_path() {
+        let server = new_server(new_app()).with_base_path("/api");
+        let text = server.get("/users").append_path("123").await.text();
+
+        assert_eq!(text, "/api/users/123");
+    }
+}
+
+#[cfg(test)]
+mod test_json_merge_patch {
+    use ::axum::http::header::CONTENT_TYPE;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::patch;
+    use ::axum::Router;
+    use ::serde_json::json;
+
+    use crate::test_utils::new_server;
+
+    async fn patch_echo(headers: HeaderMap, body: String) -> String {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "header-not-found".to_string());
+
+        format!("{} {}", content_type, body)
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/users/1", patch(patch_echo))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_json_with_merge_patch_content_type() {
+        let server = new_server(new_app());
+        let text = server
+            .patch("/users/1")
+            .json_merge_patch(&json!({ "name": "Joe", "email": null }))
+            .await
+            .text();
+
+        assert_eq!(
+            text,
+            r#"application/merge-patch+json {"email":null,"name":"Joe"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_content_type_set_before() {
+        let server = new_server(new_app());
+        let text = server
+            .patch("/users/1")
+            .content_type("application/json")
+            .json_merge_patch(&json!({ "name": "Joe" }))
+            .await
+            .text();
+
+        assert_eq!(text, r#"application/merge-patch+json {"name":"Joe"}"#);
+    }
+}
+
+#[cfg(test)]
+mod test_retry {
+    use super::*;
+
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::std::net::SocketAddr;
+    use ::std::net::TcpListener;
+    use ::std::time::Duration;
+    use ::tokio::time::sleep;
+
+    use crate::test_utils::get_ping;
+    use crate::test_utils::post_echo;
+
+    async fn get_error() -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    /// Returns an address that nothing is listening on yet.
+    fn new_free_address() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind to a free port");
+        listener.local_addr().expect("Should get the bound address")
+    }
+
+    /// Starts the app on the address given, after the delay.
+    fn start_app_after(address: SocketAddr, delay: Duration) {
+        let app = Router::new()
+            .route("/ping", get(get_ping))
+            .route("/echo", post(post_echo))
+            .route("/error", get(get_error));
+
+        ::tokio::spawn(async move {
+            sleep(delay).await;
+            ::axum::Server::bind(&address)
+                .serve(app.into_make_service())
+                .await
+                .expect("Should serve app");
+        });
+    }
+
+    #[tokio::test]
+    async fn it_should_retry_until_server_is_up() {
+        let address = new_free_address();
+        start_app_after(address, Duration::from_millis(200));
+
+        let server = Server::new(format!("http://{}", address)).expect("Should create server");
+        let text = server
+            .get("/ping")
+            .retry(50, Duration::from_millis(20))
+            .await
+            .text();
+
+        assert_eq!(text, "pong!");
+    }
+
+    #[tokio::test]
+    async fn it_should_resend_body_when_retrying() {
+        let address = new_free_address();
+        start_app_after(address, Duration::from_millis(200));
+
+        let server = Server::new(format!("http://{}", address)).expect("Should create server");
+        let text = server
+            .post("/echo")
+            .text("hello!")
+            .retry(50, Duration::from_millis(20))
+            .await
+            .text();
+
+        assert_eq!(text, "hello!");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_error_when_all_attempts_fail() {
+        let address = new_free_address();
+
+        let server = Server::new(format!("http://{}", address)).expect("Should create server");
+        let result = server
+            .get("/ping")
+            .retry(3, Duration::from_millis(10))
+            .send()
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_not_retry_error_status_codes() {
+        let address = new_free_address();
+        start_app_after(address, Duration::ZERO);
+
+        let server = Server::new(format!("http://{}", address)).expect("Should create server");
+        server
+            .get("/error")
+            .retry(50, Duration::from_millis(20))
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
+
+#[cfg(test)]
+mod test_typed_paths {
+    use ::axum::http::Uri;
+    use ::axum::Router;
+
+    use crate::test_utils::get_path;
+    use crate::test_utils::new_server;
+
+    fn new_app() -> Router {
+        Router::new().fallback(get_path)
+    }
+
+    #[tokio::test]
+    async fn it_should_request_uri_with_query() {
+        let server = new_server(new_app());
+        let path = Uri::from_static("/users?page=2&sort=name");
+        let text = server.get(path).await.text();
+
+        assert_eq!(text, "/users?page=2&sort=name");
+    }
+
+    #[tokio::test]
+    async fn it_should_request_built_uri_by_reference() {
+        let server = new_server(new_app());
+        let path = Uri::builder()
+            .path_and_query("/users/john%20doe?page=2")
+            .build()
+            .unwrap();
+        let text = server.get(&path).await.text();
+
+        assert_eq!(text, "/users/john%20doe?page=2");
+    }
+
+    #[tokio::test]
+    async fn it_should_request_string_path_by_reference() {
+        let server = new_server(new_app());
+        let path = "/users/123".to_string();
+        let text = server.delete(&path).await.text();
+
+        assert_eq!(text, "/users/123");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "is a full url")]
+    async fn it_should_reject_absolute_uri() {
+        let server = new_server(new_app());
+        let path = Uri::from_static("http://example.com/users");
+
+        let _ = server.get(path);
+    }
+
+    #[tokio::test]
+    async fn it_should_request_string_path() {
+        let server = new_server(new_app());
+        let path = format!("/users/{}", 123);
+        let text = server.post(path).await.text();
+
+        assert_eq!(text, "/users/123");
+    }
+}
+
+#[cfg(test)]
+mod test_assert_content_type {
+    use ::axum::http::header::CONTENT_TYPE;
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::Json;
+    use ::axum::Router;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+
+    use crate::test_utils::new_server;
+
+    async fn get_json() -> Json<Value> {
+        Json(json!({ "name": "Joe" }))
+    }
+
+    async fn get_text() -> &'static str {
+        "hello"
+    }
+
+    async fn get_upper_case_json() -> ([(&'static str, &'static str); 1], &'static str) {
+        ([(CONTENT_TYPE.as_str(), "Application/JSON")], "{}")
+    }
+
+    async fn get_no_content_type() -> StatusCode {
+        StatusCode::NO_CONTENT
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/json", get(get_json))
+            .route("/text", get(get_text))
+            .route("/upper-case-json", get(get_upper_case_json))
+            .route("/no-content-type", get(get_no_content_type))
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_content_type_matches() {
+        let server = new_server(new_app());
+
+        server
+            .get("/json")
+            .await
+            .assert_content_type("application/json");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_ignoring_charset() {
+        let server = new_server(new_app());
+
+        // The text response is `text/plain; charset=utf-8`.
+        server.get("/text").await.assert_content_type("text/plain");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_ignoring_case() {
+        let server = new_server(new_app());
+
+        server
+            .get("/upper-case-json")
+            .await
+            .assert_content_type("application/json");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_expected_is_upper_case() {
+        let server = new_server(new_app());
+
+        server
+            .get("/json")
+            .await
+            .assert_content_type("APPLICATION/JSON");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_assert_content_type_json_for_json() {
+        let server = new_server(new_app());
+
+        server.get("/json").await.assert_content_type_json();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "received 'text/plain; charset=utf-8'")]
+    async fn it_should_fail_when_content_type_is_different() {
+        let server = new_server(new_app());
+
+        server.get("/text").await.assert_content_type_json();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "but no content type was returned")]
+    async fn it_should_fail_when_content_type_is_missing() {
+        let server = new_server(new_app());
+
+        server
+            .get("/no-content-type")
+            .await
+            .assert_content_type_json();
+    }
+}
+
+#[cfg(test)]
+mod test_conditional_requests {
+    use ::axum::http::header::ETAG;
+    use ::axum::http::header::IF_MODIFIED_SINCE;
+    use ::axum::http::header::IF_NONE_MATCH;
+    use ::axum::http::header::LAST_MODIFIED;
+    use ::axum::http::HeaderMap;
+    use ::axum::http::StatusCode;
+    use ::axum::response::IntoResponse;
+    use ::axum::response::Response as AxumResponse;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    const TEST_ETAG: &str = r#""abc123""#;
+    const TEST_LAST_MODIFIED: &str = "Wed, 21 Oct 2015 07:28:00 GMT";
+
+    async fn get_etag(headers: HeaderMap) -> AxumResponse {
+        match headers.get(IF_NONE_MATCH) {
+            Some(etag) if etag == TEST_ETAG => StatusCode::NOT_MODIFIED.into_response(),
+            _ => ([(ETAG, TEST_ETAG)], "content").into_response(),
+        }
+    }
+
+    async fn get_last_modified(headers: HeaderMap) -> AxumResponse {
+        match headers.get(IF_MODIFIED_SINCE) {
+            Some(date) if date == TEST_LAST_MODIFIED => StatusCode::NOT_MODIFIED.into_response(),
+            _ => ([(LAST_MODIFIED, TEST_LAST_MODIFIED)], "content").into_response(),
+        }
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/etag", get(get_etag))
+            .route("/last-modified", get(get_last_modified))
+    }
+
+    #[tokio::test]
+    async fn it_should_return_not_modified_when_etag_matches() {
+        let server = new_server(new_app());
+
+        server
+            .get("/etag")
+            .if_none_match(TEST_ETAG)
+            .await
+            .assert_status(StatusCode::NOT_MODIFIED);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_content_when_etag_differs() {
+        let server = new_server(new_app());
+
+        server
+            .get("/etag")
+            .if_none_match(r#""other""#)
+            .await
+            .assert_status_ok()
+            .assert_text("content");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_not_modified_when_not_modified_since() {
+        let server = new_server(new_app());
+
+        server
+            .get("/last-modified")
+            .if_modified_since(TEST_LAST_MODIFIED)
+            .await
+            .assert_status(StatusCode::NOT_MODIFIED);
+    }
+}
+
+#[cfg(test)]
+mod test_into_hyper_request {
+    use super::*;
+
+    use ::axum::http::header::CONTENT_LENGTH;
+    use ::axum::http::header::CONTENT_TYPE;
+    use ::axum::http::header::COOKIE;
+    use ::axum::http::HeaderMap;
+    use ::axum::http::HeaderValue;
+    use ::axum::http::Method;
+    use ::axum::http::Version;
+    use ::cookie::Cookie;
+    use ::hyper::body::to_bytes;
+    use ::hyper::body::HttpBody;
+    use ::serde_json::json;
+
+    fn new_server() -> Server {
+        Server::new("http://localhost:3000".to_string()).expect("Should create server")
+    }
+
+    #[tokio::test]
+    async fn it_should_build_request_with_full_url() {
+        let server = new_server().with_base_path("/api");
+        let request = server
+            .get("/users")
+            .query_param("page", "2")
+            .into_hyper_request()
+            .expect("Should build request");
+
+        assert_eq!(request.method(), Method::GET);
+        assert_eq!(
+            request.uri().to_string(),
+            "http://localhost:3000/api/users?page=2"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_build_request_with_json_body() {
+        let server = new_server();
+        let request = server
+            .post("/users")
+            .json(&json!({ "name": "Joe" }))
+            .into_hyper_request()
+            .expect("Should build request");
+
+        assert_eq!(request.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(request.headers()[CONTENT_LENGTH], "14");
+
+        let body = to_bytes(request.into_body()).await.unwrap();
+        assert_eq!(body, r#"{"name":"Joe"}"#);
+    }
+
+    #[tokio::test]
+    async fn it_should_build_request_with_cookies() {
+        let mut server = new_server();
+        server.add_cookie(Cookie::new("session", "abc123"));
+        let request = server
+            .get("/users")
+            .add_cookie(Cookie::new("theme", "dark"))
+            .into_hyper_request()
+            .expect("Should build request");
+
+        let mut cookies: Vec<&str> = request
+            .headers()
+            .get_all(COOKIE)
+            .iter()
+            .map(|h| h.to_str().unwrap())
+            .collect();
+        cookies.sort();
+
+        assert_eq!(cookies, vec!["session=abc123", "theme=dark"]);
+    }
+
+    #[tokio::test]
+    async fn it_should_build_request_with_http_version() {
+        let server = new_server();
+        let request = server
+            .get("/users")
+            .http_version(Version::HTTP_10)
+            .into_hyper_request()
+            .expect("Should build request");
+
+        assert_eq!(request.version(), Version::HTTP_10);
+    }
+
+    #[tokio::test]
+    async fn it_should_build_chunked_request_without_content_length() {
+        let server = new_server();
+        let request = server
+            .post("/users")
+            .text("hello")
+            .chunked()
+            .into_hyper_request()
+            .expect("Should build request");
+
+        assert!(request.headers().get(CONTENT_LENGTH).is_none());
+
+        let body = to_bytes(request.into_body()).await.unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[tokio::test]
+    async fn it_should_build_request_with_trailers() {
+        let server = new_server();
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-checksum", HeaderValue::from_static("abc123"));
+        let request = server
+            .post("/users")
+            .text("hello")
+            .trailers(trailers)
+            .into_hyper_request()
+            .expect("Should build request");
+
+        let mut body = request.into_body();
+        while body.data().await.is_some() {}
+        let sent_trailers = body.trailers().await.unwrap().unwrap();
+
+        assert_eq!(sent_trailers["x-checksum"], "abc123");
+    }
+}
+
+#[cfg(test)]
+mod test_text_body {
+    use ::axum::http::header::CONTENT_TYPE;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::post;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn post_echo(headers: HeaderMap, body: String) -> String {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "header-not-found".to_string());
+
+        format!("{} {}", content_type, body)
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/echo", post(post_echo))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_str_as_text() {
+        let server = new_server(new_app());
+        let text = server.post("/echo").text("hello!").await.text();
+
+        assert_eq!(text, "text/plain hello!");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_number_as_text() {
+        let server = new_server(new_app());
+        let text = server.post("/echo").text(123).await.text();
+
+        assert_eq!(text, "text/plain 123");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_owned_string_as_text() {
+        let server = new_server(new_app());
+        let body = "hello!".to_string();
+        let text = server.post("/echo").text_string(body).await.text();
+
+        assert_eq!(text, "text/plain hello!");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_content_type_set_before_text_string() {
+        let server = new_server(new_app());
+        let text = server
+            .post("/echo")
+            .content_type("text/html")
+            .text_string("<p>hello!</p>")
+            .await
+            .text();
+
+        assert_eq!(text, "text/html <p>hello!</p>");
+    }
+}
+
+#[cfg(test)]
+mod test_trailers {
+    use ::axum::extract::RawBody;
+    use ::axum::http::HeaderMap;
+    use ::axum::http::HeaderValue;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::hyper::body::HttpBody;
+    use ::hyper::Body;
+
+    use crate::test_utils::new_server;
+
+    async fn post_read_trailers(RawBody(mut body): RawBody) -> String {
+        let mut body_text = String::new();
+        while let Some(chunk) = body.data().await {
+            body_text.push_str(&String::from_utf8_lossy(&chunk.unwrap()));
+        }
+
+        let trailers = body.trailers().await.unwrap();
+        let checksum = trailers
+            .as_ref()
+            .and_then(|trailers| trailers.get("x-checksum"))
+            .map(|h| h.to_str().unwrap().to_string())
+            .unwrap_or_else(|| "trailer-not-found".to_string());
+
+        format!("{} {}", body_text, checksum)
+    }
+
+    async fn get_with_trailers() -> ::hyper::Response<Body> {
+        let (mut sender, body) = Body::channel();
+        ::tokio::spawn(async move {
+            sender.send_data("content".into()).await.unwrap();
+
+            let mut trailers = HeaderMap::new();
+            trailers.insert("grpc-status", HeaderValue::from_static("0"));
+            sender.send_trailers(trailers).await.unwrap();
+        });
+
+        ::hyper::Response::new(body)
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/read-trailers", post(post_read_trailers))
+            .route("/with-trailers", get(get_with_trailers))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_trailers_after_body() {
+        let server = new_server(new_app());
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-checksum", HeaderValue::from_static("abc123"));
+
+        let text = server
+            .post("/read-trailers")
+            .http2_prior_knowledge()
+            .text("hello!")
+            .trailers(trailers)
+            .await
+            .text();
+
+        assert_eq!(text, "hello! abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_trailers_from_response() {
+        let server = new_server(new_app());
+        let response = server.get("/with-trailers").http2_prior_knowledge().await;
+
+        let trailers = response.trailers().expect("Should have trailers");
+        assert_eq!(response.text(), "content");
+        assert_eq!(trailers["grpc-status"], "0");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_no_trailers_when_none_sent() {
+        let server = new_server(new_app());
+        let response = server.post("/read-trailers").text("hello!").await;
+
+        assert!(response.trailers().is_none());
+        assert_eq!(response.text(), "hello! trailer-not-found");
+    }
+}
+
+#[cfg(test)]
+mod test_content_type_normalization {
+    use super::*;
+
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::serde_json::json;
+
+    use crate::test_utils::new_server;
+    use crate::test_utils::new_server_with_config;
+    use crate::test_utils::post_content_type;
+
+    fn new_app() -> Router {
+        Router::new().route("/content-type", post(post_content_type))
+    }
+
+    #[tokio::test]
+    async fn it_should_trim_content_type_keeping_case() {
+        let server = new_server(new_app());
+        let text = server
+            .post("/content-type")
+            .content_type("  Application/JSON ")
+            .json(&json!({}))
+            .await
+            .text();
+
+        assert_eq!(text, "Application/JSON");
+    }
+
+    #[tokio::test]
+    async fn it_should_trim_default_content_type() {
+        let config = ServerConfig {
+            default_content_type: Some(" APPLICATION/JSON\t".to_string()),
+            ..ServerConfig::default()
+        };
+        let server = new_server_with_config(new_app(), config);
+        let text = server.post("/content-type").await.text();
+
+        assert_eq!(text, "APPLICATION/JSON");
+    }
+}
+
+#[cfg(test)]
+mod test_expect_error_json {
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::Json;
+    use ::axum::Router;
+    use ::serde::Deserialize;
+    use ::serde::Serialize;
+
+    use crate::test_utils::new_server;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ApiError {
+        code: String,
+        message: String,
+    }
+
+    async fn get_not_found() -> (StatusCode, Json<ApiError>) {
+        let error = ApiError {
+            code: "not_found".to_string(),
+            message: "User not found".to_string(),
+        };
+
+        (StatusCode::NOT_FOUND, Json(error))
+    }
+
+    async fn get_server_error() -> (StatusCode, Json<ApiError>) {
+        let error = ApiError {
+            code: "internal".to_string(),
+            message: "Something went wrong".to_string(),
+        };
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error))
+    }
+
+    async fn get_ok() -> Json<ApiError> {
+        Json(ApiError {
+            code: "ok".to_string(),
+            message: "Not an error".to_string(),
+        })
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/not-found", get(get_not_found))
+            .route("/server-error", get(get_server_error))
+            .route("/ok", get(get_ok))
+    }
+
+    #[tokio::test]
+    async fn it_should_return_error_for_client_error() {
+        let server = new_server(new_app());
+        let error: ApiError = server.get("/not-found").await.expect_error_json();
+
+        assert_eq!(
+            error,
+            ApiError {
+                code: "not_found".to_string(),
+                message: "User not found".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_return_error_for_server_error() {
+        let server = new_server(new_app());
+        let error: ApiError = server.get("/server-error").await.expect_error_json();
+
+        assert_eq!(error.code, "internal");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected an error status code")]
+    async fn it_should_panic_for_success() {
+        let server = new_server(new_app());
+        let _: ApiError = server.get("/ok").await.expect_error_json();
+    }
+}
+
+#[cfg(test)]
+mod test_chunked {
+    use ::axum::http::header;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::post;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn post_transfer_details(headers: HeaderMap, body: String) -> String {
+        let is_chunked = headers
+            .get(header::TRANSFER_ENCODING)
+            .map(|value| value == "chunked")
+            .unwrap_or(false);
+        let has_content_length = headers.contains_key(header::CONTENT_LENGTH);
+
+        format!("chunked={is_chunked}, content-length={has_content_length}, body={body}")
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/transfer", post(post_transfer_details))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_body_with_content_length_by_default() {
+        let server = new_server(new_app());
+
+        server
+            .post("/transfer")
+            .text("hello")
+            .await
+            .assert_text("chunked=false, content-length=true, body=hello");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_body_chunked() {
+        let server = new_server(new_app());
+
+        server
+            .post("/transfer")
+            .text("hello")
+            .chunked()
+            .await
+            .assert_text("chunked=true, content-length=false, body=hello");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_body_chunked_when_following_redirects() {
+        let server = new_server(new_app());
+
+        server
+            .post("/transfer")
+            .json(&"hello")
+            .chunked()
+            .follow_redirects(3)
+            .await
+            .assert_text(r#"chunked=true, content-length=false, body="hello""#);
+    }
+}
+
+#[cfg(test)]
+mod test_path_encoding {
+    use ::axum::extract::Path;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_user_name(Path(name): Path<String>) -> String {
+        name
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/users/:name", get(get_user_name))
+    }
+
+    #[tokio::test]
+    async fn it_should_encode_spaces_in_path() {
+        let server = new_server(new_app());
+
+        server.get("/users/john doe").await.assert_text("john doe");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_double_encode_path() {
+        let server = new_server(new_app());
+
+        server
+            .get("/users/john%20doe")
+            .await
+            .assert_text("john doe");
+    }
+
+    #[tokio::test]
+    async fn it_should_encode_appended_path() {
+        let server = new_server(new_app());
+
+        server
+            .get("/users")
+            .append_path("jane doe")
+            .await
+            .assert_text("jane doe");
+    }
+}
+
+#[cfg(test)]
+mod test_with_default_content_type {
+    use super::*;
+
+    use ::axum::http::header;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::post;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn post_content_type(headers: HeaderMap) -> String {
+        headers
+            .get(header::CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default()
+    }
+
+    fn new_server_with_json_default() -> Server {
+        let app = Router::new().route("/content-type", post(post_content_type));
+        new_server(app).with_default_content_type("application/json")
+    }
+
+    #[tokio::test]
+    async fn it_should_send_default_content_type() {
+        let server = new_server_with_json_default();
+
+        server
+            .post("/content-type")
+            .text("{}")
+            .await
+            .assert_text("application/json");
+    }
+
+    #[tokio::test]
+    async fn it_should_override_default_content_type_on_request() {
+        let server = new_server_with_json_default();
+
+        server
+            .post("/content-type")
+            .content_type("text/csv")
+            .text("a,b")
+            .await
+            .assert_text("text/csv");
+    }
+
+    #[tokio::test]
+    async fn it_should_trim_default_content_type() {
+        let app = Router::new().route("/content-type", post(post_content_type));
+        let server = new_server(app).with_default_content_type(" text/csv ");
+
+        server
+            .post("/content-type")
+            .text("a,b")
+            .await
+            .assert_text("text/csv");
+    }
+}
+
+#[cfg(test)]
+mod test_duration {
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::time::Duration;
+    use ::tokio::time::sleep;
+
+    use crate::test_utils::get_fast;
+    use crate::test_utils::new_server;
+
+    async fn get_slow() -> &'static str {
+        sleep(Duration::from_millis(200)).await;
+        "slow"
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/fast", get(get_fast))
+            .route("/slow", get(get_slow))
+    }
+
+    #[tokio::test]
+    async fn it_should_measure_duration_of_slow_requests() {
+        let server = new_server(new_app());
+        let response = server.get("/slow").await;
+
+        assert!(response.duration() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_faster_than_budget() {
+        let server = new_server(new_app());
+
+        server
+            .get("/fast")
+            .await
+            .assert_faster_than(Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "to be faster than")]
+    async fn it_should_panic_when_slower_than_budget() {
+        let server = new_server(new_app());
+
+        server
+            .get("/slow")
+            .await
+            .assert_faster_than(Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod test_json_pretty {
+    use ::axum::http::header;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::serde_json::json;
+
+    use crate::test_utils::new_server;
+
+    async fn post_echo(headers: HeaderMap, body: String) -> String {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default();
+
+        format!("{content_type}\n{body}")
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/echo", post(post_echo))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_pretty_json() {
+        let server = new_server(new_app());
+
+        server
+            .post("/echo")
+            .json_pretty(&json!({ "name": "Joe" }))
+            .await
+            .assert_text("application/json\n{\n  \"name\": \"Joe\"\n}");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_content_type_already_set() {
+        let server = new_server(new_app());
+
+        server
+            .post("/echo")
+            .content_type("application/vnd.api+json")
+            .json_pretty(&json!([1]))
+            .await
+            .assert_text("application/vnd.api+json\n[\n  1\n]");
+    }
+}
+
+#[cfg(test)]
+mod test_max_response_size {
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_large() -> Vec<u8> {
+        vec![b'a'; 2048]
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/large", get(get_large))
+    }
+
+    #[tokio::test]
+    async fn it_should_read_body_within_max_size() {
+        let server = new_server(new_app());
+        let response = server.get("/large").max_response_size(2048).await;
+
+        assert_eq!(response.as_bytes().len(), 2048);
+    }
+
+    #[tokio::test]
+    async fn it_should_error_when_body_is_over_max_size() {
+        let server = new_server(new_app());
+        let error = server
+            .get("/large")
+            .max_response_size(1024)
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(format!("{:?}", error).contains("larger than the maximum size of 1024 bytes"));
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_when_body_is_over_max_size() {
+        let server = new_server(new_app());
+        let _ = server.get("/large").max_response_size(1024).await;
+    }
+}
+
+#[cfg(test)]
+mod test_new_with_port {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::net::TcpListener;
+
+    use crate::test_utils::get_ping;
+
+    fn new_app() -> Router {
+        Router::new().route("/ping", get(get_ping))
+    }
+
+    fn find_free_port() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind to a free port");
+        listener.local_addr().unwrap().port()
+    }
+
+    #[tokio::test]
+    async fn it_should_run_app_on_port_given() {
+        let port = find_free_port();
+        let server = Server::new_with_port(new_app(), port).expect("Should create server");
+
+        assert_eq!(server.server_address().port_u16(), Some(port));
+        server.get("/ping").await.assert_text("pong!");
+    }
+
+    #[tokio::test]
+    async fn it_should_error_when_port_is_in_use() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind to a free port");
+        let port = listener.local_addr().unwrap().port();
+
+        let error = Server::new_with_port(new_app(), port).unwrap_err();
+
+        assert!(format!("{:?}", error).contains("is the port already in use?"));
+    }
+}
+
+#[cfg(test)]
+mod test_server_shutdown {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::net::TcpListener;
+    use ::std::time::Duration;
+    use ::tokio::time::sleep;
+
+    use crate::test_utils::get_ping;
+    use crate::test_utils::new_server;
+
+    fn new_app() -> Router {
+        Router::new().route("/ping", get(get_ping))
+    }
+
+    /// Binds to the port, waiting for it to be released if it's in use.
+    async fn new_server_with_retries(port: u16) -> Server {
+        for _ in 0..100 {
+            if let Ok(server) = Server::new_with_port(new_app(), port) {
+                return server;
+            }
+
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        panic!("Port {} was never released", port);
+    }
+
+    #[tokio::test]
+    async fn it_should_release_port_when_dropped() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind to a free port");
+            listener.local_addr().unwrap().port()
+        };
+
+        for _ in 0..20 {
+            let server = new_server_with_retries(port).await;
+            server.get("/ping").await.assert_text("pong!");
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_running_while_a_clone_exists() {
+        let server = new_server(new_app());
+        let cloned_server = server.clone();
+        ::std::mem::drop(server);
+
+        sleep(Duration::from_millis(10)).await;
+        cloned_server.get("/ping").await.assert_text("pong!");
+    }
+}
+
+#[cfg(test)]
+mod test_assert_json_contains {
+    use ::axum::routing::get;
+    use ::axum::Json;
+    use ::axum::Router;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+
+    use crate::test_utils::new_server;
+
+    async fn get_user() -> Json<Value> {
+        Json(json!({
+            "id": 123,
+            "name": "Joe",
+            "created_at": "2023-01-01T00:00:00Z",
+        }))
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/user", get(get_user))
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_json_contains_partial() {
+        let server = new_server(new_app());
+
+        server
+            .get("/user")
+            .await
+            .assert_json_contains(json!({ "name": "Joe" }));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "to contain")]
+    async fn it_should_panic_when_json_differs() {
+        let server = new_server(new_app());
+
+        server
+            .get("/user")
+            .await
+            .assert_json_contains(json!({ "name": "Jane" }));
+    }
+}
+
+#[cfg(test)]
+mod test_raw_header {
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_custom_header(headers: HeaderMap) -> String {
+        headers
+            .get("x-custom")
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default()
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/header", get(get_custom_header))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_raw_header() {
+        let server = new_server(new_app());
+
+        server
+            .get("/header")
+            .raw_header("X-Custom: my value")
+            .await
+            .assert_text("my value");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_colons_in_value() {
+        let server = new_server(new_app());
+
+        server
+            .get("/header")
+            .raw_header("X-Custom:http://example.com")
+            .await
+            .assert_text("http://example.com");
+    }
+
+    #[tokio::test]
+    async fn it_should_error_without_colon() {
+        let server = new_server(new_app());
+        let result = server.get("/header").try_raw_header("X-Custom my value");
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_error_with_invalid_name() {
+        let server = new_server(new_app());
+        let result = server.get("/header").try_raw_header("X Custom: my value");
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Failed to parse raw header")]
+    async fn it_should_panic_when_malformed() {
+        let server = new_server(new_app());
+        let _ = server.get("/header").raw_header("X-Custom");
+    }
+}
+
+#[cfg(test)]
+mod test_json_value {
+    use ::axum::http::header;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::serde_json::json;
+
+    use crate::test_utils::new_server;
+
+    async fn post_echo(headers: HeaderMap, body: String) -> String {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default();
+
+        format!("{content_type} {body}")
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/echo", post(post_echo))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_json_value() {
+        let server = new_server(new_app());
+
+        server
+            .post("/echo")
+            .json_value(json!({ "name": "Joe" }))
+            .await
+            .assert_text(r#"application/json {"name":"Joe"}"#);
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_content_type_already_set() {
+        let server = new_server(new_app());
+
+        server
+            .post("/echo")
+            .content_type("application/vnd.api+json")
+            .json_value(json!([1, 2]))
+            .await
+            .assert_text("application/vnd.api+json [1,2]");
+    }
+}
+
+#[cfg(test)]
+mod test_get_many {
+    use ::axum::extract::State;
+    use ::axum::http::header::SET_COOKIE;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::sync::atomic::AtomicUsize;
+    use ::std::sync::atomic::Ordering;
+    use ::std::sync::Arc;
+
+    use crate::test_utils::new_server;
+
+    async fn get_count(
+        State(counter): State<Arc<AtomicUsize>>,
+    ) -> ([(&'static str, &'static str); 1], String) {
+        let count = counter.fetch_add(1, Ordering::SeqCst) + 1;
+
+        ([(SET_COOKIE.as_str(), "visited=true")], count.to_string())
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/count", get(get_count))
+            .with_state(Arc::new(AtomicUsize::new(0)))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_all_requests() {
+        let server = new_server(new_app());
+        let responses = server.get_many("/count", 10).await;
+
+        let mut counts: Vec<usize> = responses
+            .iter()
+            .map(|response| response.text().parse().unwrap())
+            .collect();
+        counts.sort();
+
+        assert_eq!(counts, (1..=10).collect::<Vec<usize>>());
+    }
+
+    #[tokio::test]
+    async fn it_should_return_no_responses_for_zero_requests() {
+        let server = new_server(new_app());
+        let responses = server.get_many("/count", 0).await;
+
+        assert!(responses.is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_should_not_save_cookies() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+
+        server.get_many("/count", 3).await;
+
+        assert!(server.maybe_cookie("visited").is_none());
+    }
+}
+
+#[cfg(test)]
+mod test_timeout {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::time::Duration;
+
+    use crate::test_utils::get_fast;
+    use crate::test_utils::get_slow;
+    use crate::test_utils::new_server;
+    use crate::test_utils::new_server_with_config;
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/fast", get(get_fast))
+            .route("/slow", get(get_slow))
+    }
+
+    #[tokio::test]
+    async fn it_should_succeed_within_timeout() {
+        let server = new_server(new_app());
+
+        server
+            .get("/fast")
+            .timeout(Duration::from_secs(5))
+            .await
+            .assert_text("fast");
+    }
+
+    #[tokio::test]
+    async fn it_should_error_when_request_times_out() {
+        let server = new_server(new_app());
+        let error = server
+            .get("/slow")
+            .timeout(Duration::from_millis(50))
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("timed out after 50ms"));
+    }
+
+    #[tokio::test]
+    async fn it_should_use_default_timeout_from_server() {
+        let server = new_server(new_app()).with_default_timeout(Duration::from_millis(50));
+        let result = server.get("/slow").send().await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_use_default_timeout_from_config() {
+        let config = ServerConfig {
+            default_timeout: Some(Duration::from_millis(50)),
+            ..ServerConfig::default()
+        };
+        let server = new_server_with_config(new_app(), config);
+        let result = server.get("/slow").send().await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_override_default_timeout_on_request() {
+        let server = new_server(new_app()).with_default_timeout(Duration::from_millis(50));
+
+        server
+            .get("/slow")
+            .timeout(Duration::from_secs(5))
+            .await
+            .assert_text("slow");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "timed out")]
+    async fn it_should_panic_when_awaited_and_timed_out() {
+        let server = new_server(new_app());
+        let _ = server.get("/slow").timeout(Duration::from_millis(50)).await;
+    }
+}
+
+#[cfg(test)]
+mod test_into_parts {
+    use super::*;
+
+    use ::axum::http::StatusCode;
+    use ::axum::http::Version;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    async fn get_created() -> (StatusCode, [(&'static str, &'static str); 1], &'static str) {
+        (StatusCode::CREATED, [("x-id", "123")], "created!")
+    }
+
+    fn new_app() -> Router {
+        Router::new().route("/users/new", get(get_created))
+    }
+
+    #[tokio::test]
+    async fn it_should_return_path_parts_and_body() {
+        let server = new_server(new_app());
+        let (path, parts, body) = server.get("/users/new?name=Joe").await.into_parts();
+
+        assert_eq!(path, "/users/new");
+        assert_eq!(parts.status, StatusCode::CREATED);
+        assert_eq!(parts.version, Version::HTTP_11);
+        assert_eq!(parts.headers.get("x-id").unwrap(), "123");
+        assert_eq!(body, "created!");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_http2_version() {
+        let server = new_server(new_app());
+        let (_, parts, _) = server
+            .get("/users/new")
+            .http2_prior_knowledge()
+            .await
+            .into_parts();
+
+        assert_eq!(parts.version, Version::HTTP_2);
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_when_request_failed() {
+        let server = Server::new("http://127.0.0.1:1".to_string()).expect("Should create server");
+        let _ = server.get("/users/new").expect_failure().await.into_parts();
+    }
+}
+
+#[cfg(test)]
+mod test_scope_cookies {
+    use ::axum::http::header::COOKIE;
+    use ::axum::http::HeaderMap;
+    use ::axum::response::Redirect;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::cookie::Cookie;
+
+    use crate::test_utils::new_server;
+
+    async fn get_cookie_header(headers: HeaderMap) -> String {
+        headers
+            .get(COOKIE)
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default()
+    }
+
+    async fn get_redirect_out() -> Redirect {
+        Redirect::temporary("/cookies")
+    }
+
+    async fn get_redirect_within() -> Redirect {
+        Redirect::temporary("/admin/cookies")
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/cookies", get(get_cookie_header))
+            .route("/admin/cookies", get(get_cookie_header))
+            .route("/admin/redirect-out", get(get_redirect_out))
+            .route("/admin/redirect-within", get(get_redirect_within))
+    }
+
+    #[tokio::test]
+    async fn it_should_send_cookie_to_request_path() {
+        let server = new_server(new_app());
+
+        server
+            .get("/admin/cookies")
+            .scope_cookies()
+            .add_cookie(Cookie::new("session", "abc123"))
+            .await
+            .assert_text("session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_scoped_cookie_when_redirected_within_path() {
+        let server = new_server(new_app());
+
+        server
+            .get("/admin/redirect-within")
+            .scope_cookies()
+            .add_cookie(Cookie::new("session", "abc123"))
+            .follow_redirects(1)
+            .await
+            .assert_text("session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_scoped_cookie_when_redirected_outside_path() {
+        let server = new_server(new_app());
+
+        server
+            .get("/admin/redirect-out")
+            .scope_cookies()
+            .add_cookie(Cookie::new("session", "abc123"))
+            .follow_redirects(1)
+            .await
+            .assert_text("");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_unscoped_cookie_when_redirected_outside_path() {
+        let server = new_server(new_app());
+
+        server
+            .get("/admin/redirect-out")
+            .add_cookie(Cookie::new("session", "abc123"))
+            .follow_redirects(1)
+            .await
+            .assert_text("session=abc123");
+    }
+}
+
+#[cfg(test)]
+mod test_reset {
+    use super::*;
+
+    use ::axum::http::header::COOKIE;
+    use ::axum::http::header::SET_COOKIE;
+    use ::axum::http::HeaderMap;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::cookie::Cookie;
+
+    use crate::test_utils::new_server;
+    use crate::test_utils::new_server_with_config;
+
+    async fn get_cookie_header(headers: HeaderMap) -> String {
+        headers
+            .get(COOKIE)
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default()
+    }
+
+    async fn post_login() -> [(&'static str, &'static str); 1] {
+        [(SET_COOKIE.as_str(), "session=abc123")]
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/cookies", get(get_cookie_header))
+            .route("/login", post(post_login))
+    }
+
+    #[tokio::test]
+    async fn it_should_clear_cookies() {
+        let mut server = new_server(new_app());
+        server.add_cookie(Cookie::new("theme", "dark"));
+
+        server.reset();
+
+        server.get("/cookies").await.assert_text("");
+    }
+
+    #[tokio::test]
+    async fn it_should_stop_saving_cookies_when_turned_on_after_creation() {
+        let mut server = new_server(new_app());
+        server.save_cookies();
+
+        server.reset();
+        server.post("/login").await;
+
+        assert!(server.maybe_cookie("session").is_none());
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_saving_cookies_when_configured() {
+        let config = ServerConfig {
+            save_cookies: true,
+            ..ServerConfig::default()
+        };
+        let mut server = new_server_with_config(new_app(), config);
+        server.do_not_save_cookies();
+
+        server.reset();
+        server.post("/login").await;
+
+        server.get("/cookies").await.assert_text("session=abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_base_path() {
+        let app = Router::new().nest("/api", new_app());
+        let mut server = new_server(app).with_base_path("/api");
+
+        server.reset();
+
+        server
+            .get("/cookies")
+            .await
+            .assert_status_ok()
+            .assert_text("");
+    }
+}
+
+#[cfg(test)]
+mod test_map_request {
+    use ::axum::http::HeaderMap;
+    use ::axum::response::Redirect;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::test_utils::new_server;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct TraceId(&'static str);
+
+    async fn get_trace_headers(headers: HeaderMap) -> String {
+        headers
+            .get_all("x-trace")
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    async fn get_redirect() -> Redirect {
+        Redirect::temporary("/trace")
+    }
+
+    fn new_app() -> Router {
+        Router::new()
+            .route("/trace", get(get_trace_headers))
+            .route("/redirect", get(get_redirect))
+    }
+
+    #[tokio::test]
+    async fn it_should_apply_mapper_to_request() {
+        let server = new_server(new_app());
+
+        server
+            .get("/trace")
+            .map_request(|builder| builder.header("x-trace", "first"))
+            .await
+            .assert_text("first");
+    }
+
+    #[tokio::test]
+    async fn it_should_apply_mappers_in_order() {
+        let server = new_server(new_app());
+
+        server
+            .get("/trace")
+            .map_request(|builder| builder.header("x-trace", "first"))
+            .map_request(|builder| builder.header("x-trace", "second"))
+            .await
+            .assert_text("first, second");
+    }
+
+    #[tokio::test]
+    async fn it_should_apply_mapper_when_following_redirects() {
+        let server = new_server(new_app());
+
+        server
+            .get("/redirect")
+            .follow_redirects(1)
+            .map_request(|builder| builder.header("x-trace", "first"))
+            .await
+            .assert_text("first");
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_adding_extensions() {
+        let server = new_server(new_app());
+        let request = server
+            .get("/trace")
+            .map_request(|builder| builder.extension(TraceId("abc123")))
+            .into_hyper_request()
+            .expect("Should build request");
+
+        assert_eq!(
+            request.extensions().get::<TraceId>(),
+            Some(&TraceId("abc123"))
+        );
+    }
 }
@@ -0,0 +1,146 @@
+use ::anyhow::Context;
+use ::hyper::body::Bytes;
+use ::hyper::http::HeaderValue;
+use ::std::collections::hash_map::RandomState;
+use ::std::hash::BuildHasher;
+use ::std::hash::Hasher;
+
+///
+/// A `MultipartForm` is a `multipart/form-data` body,
+/// for sending with `Request::multipart`.
+///
+/// It is built up from text fields, and file parts.
+///
+/// ```rust
+/// use ::kantan::MultipartForm;
+///
+/// let form = MultipartForm::new()
+///     .add_text("name", "Terrance Pencilworth")
+///     .add_file("avatar", "avatar.png", "image/png", vec![0x89, 0x50, 0x4e, 0x47]);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct MultipartForm {
+    boundary: String,
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone)]
+struct Part {
+    name: String,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    contents: Bytes,
+}
+
+impl MultipartForm {
+    /// Creates a new empty form, with a randomly generated boundary.
+    pub fn new() -> Self {
+        let random = RandomState::new().build_hasher().finish();
+
+        Self {
+            boundary: format!("kantan-boundary-{:016x}", random),
+            parts: vec![],
+        }
+    }
+
+    /// Adds a text field to the form.
+    pub fn add_text<T>(mut self, name: &str, text: T) -> Self
+    where
+        T: ToString,
+    {
+        self.parts.push(Part {
+            name: name.to_string(),
+            file_name: None,
+            content_type: None,
+            contents: Bytes::from(text.to_string()),
+        });
+
+        self
+    }
+
+    /// Adds a file to the form, with the file name and content type given.
+    ///
+    /// This will panic if the content type is not a valid header value,
+    /// such as when it contains a line break.
+    pub fn add_file<B>(
+        mut self,
+        name: &str,
+        file_name: &str,
+        content_type: &str,
+        contents: B,
+    ) -> Self
+    where
+        B: Into<Bytes>,
+    {
+        // The content type is written into the headers of the part.
+        HeaderValue::from_str(content_type)
+            .with_context(|| {
+                format!(
+                    "Failed to use {:?} as the content type for file '{}'",
+                    content_type, file_name
+                )
+            })
+            .unwrap();
+
+        self.parts.push(Part {
+            name: name.to_string(),
+            file_name: Some(file_name.to_string()),
+            content_type: Some(content_type.to_string()),
+            contents: contents.into(),
+        });
+
+        self
+    }
+
+    /// The value to use for the `Content-Type` header when sending this form.
+    pub(crate) fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// Serializes all of the parts into the body to be sent.
+    pub(crate) fn into_bytes(self) -> Bytes {
+        let mut body: Vec<u8> = vec![];
+
+        for part in self.parts {
+            body.extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+            body.extend_from_slice(
+                format!(
+                    "Content-Disposition: form-data; name=\"{}\"",
+                    escape_param(&part.name)
+                )
+                .as_bytes(),
+            );
+            if let Some(file_name) = part.file_name {
+                body.extend_from_slice(
+                    format!("; filename=\"{}\"", escape_param(&file_name)).as_bytes(),
+                );
+            }
+            body.extend_from_slice(b"\r\n");
+            if let Some(content_type) = part.content_type {
+                body.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+            }
+            body.extend_from_slice(b"\r\n");
+            body.extend_from_slice(&part.contents);
+            body.extend_from_slice(b"\r\n");
+        }
+
+        body.extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+
+        Bytes::from(body)
+    }
+}
+
+impl Default for MultipartForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Percent-encodes quotes and line breaks, like browsers do,
+/// so a name cannot end the parameter or add extra headers to the part.
+fn escape_param(text: &str) -> String {
+    text.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
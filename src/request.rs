@@ -1,36 +1,86 @@
 use ::anyhow::anyhow;
 use ::anyhow::Context;
+use ::anyhow::Error;
 use ::anyhow::Result;
 use ::auto_future::AutoFuture;
+use ::base64::engine::general_purpose::STANDARD as BASE64;
+use ::base64::Engine;
 use ::cookie::Cookie;
 use ::cookie::CookieJar;
+use ::futures_core::Stream;
 use ::hyper::body::to_bytes;
 use ::hyper::body::Body;
 use ::hyper::body::Bytes;
+use ::hyper::body::HttpBody;
 use ::hyper::header;
 use ::hyper::header::HeaderName;
 use ::hyper::http::header::SET_COOKIE;
+use ::hyper::http::request::Builder as RequestBuilder;
+use ::hyper::http::Error as HttpError;
+use ::hyper::http::HeaderMap;
 use ::hyper::http::HeaderValue;
+use ::hyper::http::Method;
 use ::hyper::http::Request as HyperRequest;
-use ::hyper::Client;
-use ::hyper_tls::HttpsConnector;
+use ::hyper::http::StatusCode;
+use ::hyper::http::Uri;
+use ::hyper::http::Version;
 use ::serde::Serialize;
 use ::serde_json::to_vec as json_to_vec;
+use ::serde_json::to_vec_pretty as json_to_vec_pretty;
+use ::serde_json::Value;
 use ::std::convert::AsRef;
 use ::std::fmt::Debug;
 use ::std::fmt::Display;
 use ::std::future::IntoFuture;
 use ::std::sync::Arc;
 use ::std::sync::Mutex;
+use ::std::time::Duration;
+use ::std::time::Instant;
+use ::tokio::time::sleep;
+use ::tokio::time::timeout;
 
+use crate::encode_path;
+use crate::join_paths;
+use crate::HttpClient;
 use crate::InnerServer;
+use crate::MultipartForm;
 use crate::Response;
 
+mod cookie_matching;
+pub(crate) use self::cookie_matching::*;
+
+mod debug_log;
+use self::debug_log::*;
+
+mod decompress;
+use self::decompress::*;
+
+mod expected_failure;
+pub(crate) use self::expected_failure::*;
+
+mod expected_status;
+pub(crate) use self::expected_status::*;
+
 mod request_config;
 pub(crate) use self::request_config::*;
 
-const JSON_CONTENT_TYPE: &'static str = &"application/json";
-const TEXT_CONTENT_TYPE: &'static str = &"text/plain";
+mod request_mapper;
+use self::request_mapper::*;
+
+mod request_details;
+pub(crate) use self::request_details::*;
+
+mod trailers;
+use self::trailers::*;
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const JSON_MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+const TEXT_CONTENT_TYPE: &str = "text/plain";
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+/// The largest response body that will be read, in bytes.
+const DEFAULT_MAX_RESPONSE_SIZE: usize = 8 * 1024 * 1024;
 
 ///
 /// A `Request` represents a HTTP request to the test server.
@@ -67,43 +117,84 @@ const TEXT_CONTENT_TYPE: &'static str = &"text/plain";
 #[derive(Debug)]
 #[must_use = "futures do nothing unless polled"]
 pub struct Request {
+    details: RequestDetails,
     config: RequestConfig,
 
     inner_test_server: Arc<Mutex<InnerServer>>,
+    client: HttpClient,
 
     body: Option<Body>,
     headers: Vec<(HeaderName, HeaderValue)>,
     cookies: CookieJar,
 
     is_saving_cookies: bool,
+    is_sending_cookies: bool,
+    is_scoping_cookies: bool,
+    expected_failure: Option<ExpectedFailure>,
+    expected_status: Option<ExpectedStatus>,
+    max_redirects: usize,
+    is_decompressing: Option<bool>,
+    http_version: Option<Version>,
+    is_http2_prior_knowledge: bool,
+    is_sending_host_header: bool,
+    is_debugging: bool,
+    is_debugging_unredacted: bool,
+    max_retries: usize,
+    retry_delay: Duration,
+    trailers: Option<HeaderMap>,
+    is_chunked: bool,
+    max_response_size: usize,
+    request_mappers: Vec<RequestMapper>,
 }
 
 impl Request {
     pub(crate) fn new(
         inner_test_server: Arc<Mutex<InnerServer>>,
+        details: RequestDetails,
         config: RequestConfig,
     ) -> Result<Self> {
         let is_saving_cookies = config.save_cookies;
+        let headers = config.default_headers.clone();
         let server_locked = inner_test_server.as_ref().lock().map_err(|err| {
             anyhow!(
                 "Failed to lock InternalServer for {} {}, received {:?}",
-                config.method,
-                config.request_path,
+                details.method,
+                details.request_path,
                 err
             )
         })?;
 
-        let cookies = server_locked.cookies().clone();
+        let cookies = server_locked.cookies()?;
+        let client = server_locked.client().clone();
 
         ::std::mem::drop(server_locked);
 
         Ok(Self {
+            details,
             config,
             inner_test_server,
+            client,
             body: None,
-            headers: vec![],
+            headers,
             cookies,
             is_saving_cookies,
+            is_sending_cookies: true,
+            is_scoping_cookies: false,
+            expected_failure: None,
+            expected_status: None,
+            max_redirects: 0,
+            is_decompressing: None,
+            http_version: None,
+            is_http2_prior_knowledge: false,
+            is_sending_host_header: true,
+            is_debugging: false,
+            is_debugging_unredacted: false,
+            max_retries: 0,
+            retry_delay: Duration::ZERO,
+            trailers: None,
+            is_chunked: false,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            request_mappers: vec![],
         })
     }
 
@@ -124,45 +215,697 @@ impl Request {
         self
     }
 
+    /// Marks that this request is expected to fail to reach the server.
+    ///
+    /// When awaited, this will panic if the request succeeds.
+    /// If the request does fail, then a `Response` with no status code,
+    /// headers, or body is returned instead.
+    pub fn expect_failure(mut self) -> Self {
+        self.expected_failure = Some(ExpectedFailure::Any);
+        self
+    }
+
+    /// Marks that this request is expected to have its connection refused,
+    /// such as when nothing is listening on the port.
+    ///
+    /// This works like `Request::expect_failure`,
+    /// and will also panic if the request fails for any other reason.
+    pub fn expect_connection_refused(mut self) -> Self {
+        self.expected_failure = Some(ExpectedFailure::ConnectionRefused);
+        self
+    }
+
+    /// Marks that this request is expected to time out,
+    /// such as from `Request::timeout`.
+    ///
+    /// This works like `Request::expect_failure`,
+    /// and will also panic if the request fails for any other reason.
+    pub fn expect_timeout(mut self) -> Self {
+        self.expected_failure = Some(ExpectedFailure::Timeout);
+        self
+    }
+
+    /// Marks that this request is expected to return a 2xx status code.
+    ///
+    /// When awaited, this will panic if any other status code is returned.
+    pub fn expect_success(mut self) -> Self {
+        self.expected_status = Some(ExpectedStatus::Success);
+        self
+    }
+
+    /// Marks that this request is expected to return the status code given.
+    ///
+    /// When awaited, this will panic if any other status code is returned.
+    pub fn expect_status(mut self, status_code: StatusCode) -> Self {
+        self.expected_status = Some(ExpectedStatus::Exact(status_code));
+        self
+    }
+
+    /// Marks that this request is expected to return one of the status codes given.
+    ///
+    /// When awaited, this will panic if any other status code is returned.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::put;
+    /// use ::kantan::http::StatusCode;
+    /// # use ::kantan::Server;
+    /// #
+    /// # let app = Router::new().route("/users/1", put(|| async { StatusCode::CREATED }));
+    /// # let server = Server::new_with_router(app).unwrap();
+    ///
+    /// server
+    ///     .put("/users/1")
+    ///     .expect_status_in(&[StatusCode::OK, StatusCode::CREATED])
+    ///     .await;
+    /// # })
+    /// ```
+    pub fn expect_status_in(mut self, status_codes: &[StatusCode]) -> Self {
+        self.expected_status = Some(ExpectedStatus::OneOf(status_codes.to_vec()));
+        self
+    }
+
+    /// Follows any redirects returned by the server,
+    /// up to the maximum number of redirects given.
+    ///
+    /// Cookies returned along the way are sent on to the next request.
+    /// If the maximum is reached, then the last redirect is returned as the `Response`.
+    ///
+    /// When redirected to a different host, the `Authorization` header
+    /// and any cookies without a `Domain` are no longer sent.
+    ///
+    /// By default redirects are not followed.
+    pub fn follow_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    /// Retries sending the request if it fails to connect to the server,
+    /// making up to the number of attempts given in total.
+    /// There is a delay between each attempt.
+    ///
+    /// This is useful when the server may still be starting up.
+    /// Only connection errors are retried, error status codes are not.
+    /// If every attempt fails, then the last error is returned.
+    ///
+    /// When retrying, the request body is read into memory before it is sent.
+    pub fn retry(mut self, attempts: usize, delay: Duration) -> Self {
+        self.max_retries = attempts.saturating_sub(1);
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Changes the underlying Hyper request builder, before the body is added.
+    ///
+    /// This is an escape hatch for anything not directly supported,
+    /// such as adding extensions to the request. Calling this multiple times
+    /// will apply each function in turn.
+    ///
+    /// The function is called for each request sent,
+    /// including when following redirects or retrying.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::get;
+    /// # use ::kantan::Server;
+    /// #
+    /// # let app = Router::new().route("/users", get(|| async {}));
+    /// # let server = Server::new_with_router(app).unwrap();
+    /// let response = server
+    ///     .get("/users")
+    ///     .map_request(|builder| builder.header("x-trace-id", "abc123"))
+    ///     .await;
+    /// # })
+    /// ```
+    pub fn map_request<F>(mut self, mapper: F) -> Self
+    where
+        F: Fn(RequestBuilder) -> RequestBuilder + Send + Sync + 'static,
+    {
+        self.request_mappers.push(RequestMapper::new(mapper));
+        self
+    }
+
+    /// Sets the longest time this request can take.
+    /// If it takes longer, then the request will fail.
+    ///
+    /// This includes following redirects, retries, and reading the body.
+    /// This overrides the default timeout from the `Server`.
+    pub fn timeout(mut self, duration: Duration) -> Self {
+        self.config.timeout = Some(duration);
+        self
+    }
+
+    /// Sets the largest response body that will be read, in bytes.
+    /// If the body is larger, then the request will fail.
+    ///
+    /// This guards against a server returning an endless body.
+    /// The limit applies to the body as sent, and again once it is decompressed.
+    ///
+    /// **Defaults** to 8 megabytes.
+    pub fn max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Sets if the response body should be decompressed,
+    /// based on its `Content-Encoding` header.
+    /// This supports `gzip`, `deflate`, and `br`.
+    ///
+    /// When decompressing, an `Accept-Encoding` header listing these is sent,
+    /// unless one has already been set. Once decompressed, the `Content-Encoding` header
+    /// is removed from the `Response`, and the `Content-Length` matches the decompressed body.
+    /// Bodies using an encoding which isn't supported are also left unchanged.
+    ///
+    /// By default responses are only decompressed when the `Accept-Encoding`
+    /// header is the one sent by this. If you set your own `Accept-Encoding`,
+    /// call this with `true` to have the response decompressed too.
+    pub fn decompress(mut self, is_decompressing: bool) -> Self {
+        self.is_decompressing = Some(is_decompressing);
+        self
+    }
+
+    /// The response body will not be decompressed,
+    /// and no `Accept-Encoding` header is sent for it.
+    ///
+    /// This is the same as calling `Request::decompress(false)`.
+    pub fn no_decompress(self) -> Self {
+        self.decompress(false)
+    }
+
+    /// Sends this request using HTTP/2, without first negotiating with the server.
+    /// This is also known as HTTP/2 with 'prior knowledge'.
+    ///
+    /// The server must support HTTP/2 for this to work.
+    /// Requests to a server which only speaks HTTP/1 will fail.
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.is_http2_prior_knowledge = true;
+        self
+    }
+
+    /// Sets the HTTP version to use for this request, such as `Version::HTTP_10`.
+    ///
+    /// By default the version is chosen by the underlying client.
+    pub fn http_version(mut self, version: Version) -> Self {
+        self.http_version = Some(version);
+        self
+    }
+
+    /// Sets if the request and response should be logged,
+    /// using the `log` crate at the debug level.
+    ///
+    /// This includes the headers and the body. The `Authorization`,
+    /// `Cookie`, and `Set-Cookie` headers are redacted,
+    /// unless `Request::debug_unredacted` is used.
+    ///
+    /// When logging, the request body is read into memory before it is sent.
+    pub fn debug(mut self, is_debugging: bool) -> Self {
+        self.is_debugging = is_debugging;
+        self
+    }
+
+    /// Logs the request and response like `Request::debug`,
+    /// without redacting any headers.
+    pub fn debug_unredacted(mut self) -> Self {
+        self.is_debugging = true;
+        self.is_debugging_unredacted = true;
+        self
+    }
+
     /// Clears all cookies used internally within this Request.
     pub fn clear_cookies(mut self) -> Self {
         self.cookies = CookieJar::new();
         self
     }
 
+    /// No cookies will be sent with this request.
+    ///
+    /// This includes cookies from the `Server`,
+    /// and any cookies added to this request, before or after calling this.
+    pub fn no_cookies(mut self) -> Self {
+        self.is_sending_cookies = false;
+        self
+    }
+
+    /// Cookies without a `Domain` or `Path` will be scoped to this request,
+    /// like a browser would. The domain is set to the host of the request,
+    /// and the path to its directory. i.e. `/admin` for `/admin/users`.
+    ///
+    /// This is applied when the request is sent, and so affects which cookies
+    /// are passed on when following redirects.
+    ///
+    /// By default cookies are sent as is.
+    pub fn scope_cookies(mut self) -> Self {
+        self.is_scoping_cookies = true;
+        self
+    }
+
     /// Adds a Cookie to be sent with this request.
     pub fn add_cookie<'c>(mut self, cookie: Cookie<'c>) -> Self {
         self.cookies.add(cookie.into_owned());
         self
     }
 
+    /// Adds extra cookies to be sent with this request.
+    ///
+    /// Any cookies which have the same name as the new cookies,
+    /// will get replaced.
+    pub fn add_cookies(mut self, cookies: CookieJar) -> Self {
+        for cookie in cookies.iter() {
+            self.cookies.add(cookie.to_owned());
+        }
+
+        self
+    }
+
+    /// Replaces all of the cookies to be sent with this request,
+    /// with those in the jar given.
+    ///
+    /// Cookies from the `Server` are not sent, unless they are in the jar.
+    /// Cookies returned are still saved to the `Server`, if it is saving cookies.
+    pub fn with_cookie_jar(mut self, cookies: CookieJar) -> Self {
+        self.cookies = cookies;
+        self
+    }
+
+    /// Removes the cookie with the given name from this request.
+    ///
+    /// Other cookies are left untouched.
+    pub fn remove_cookie(mut self, name: &str) -> Self {
+        self.cookies.force_remove(&Cookie::named(name.to_string()));
+        self
+    }
+
+    /// Adds a header to be sent with this request.
+    ///
+    /// The name and value can be anything that converts into a
+    /// `HeaderName` and `HeaderValue`, such as a `&str`.
+    /// This will panic if either fails to convert.
+    ///
+    /// Adding the same header multiple times will send all of the values.
+    /// However if the `Server` has a default header with the same name,
+    /// then this will replace it, rather than sending both.
+    pub fn add_header<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: TryInto<HeaderName>,
+        N::Error: Into<HttpError>,
+        V: TryInto<HeaderValue>,
+        V::Error: Into<HttpError>,
+    {
+        let header_name: HeaderName = name
+            .try_into()
+            .map_err(Into::into)
+            .context("Failed to build header name for request")
+            .unwrap();
+        let header_value: HeaderValue = value
+            .try_into()
+            .map_err(Into::into)
+            .with_context(|| format!("Failed to build value for header '{}'", header_name))
+            .unwrap();
+
+        // The first header set with the same name as a default replaces the default.
+        let is_default_header = self
+            .config
+            .default_headers
+            .iter()
+            .any(|(name, _)| name == header_name);
+        if is_default_header {
+            self.config
+                .default_headers
+                .retain(|(name, _)| name != header_name);
+            self.headers.retain(|(name, _)| name != header_name);
+        }
+
+        self.headers.push((header_name, header_value));
+        self
+    }
+
+    /// Adds a header written as a single line, such as `"Accept: text/html"`.
+    ///
+    /// This is useful for copying headers from curl or browser tools.
+    /// It works like `Request::add_header`, and will panic if the header is malformed.
+    /// See `Request::try_raw_header` for a version which returns the error.
+    pub fn raw_header(self, raw_header: &str) -> Self {
+        self.try_raw_header(raw_header)
+            .with_context(|| format!("Failed to parse raw header '{}'", raw_header))
+            .unwrap()
+    }
+
+    /// Adds a header written as a single line, such as `"Accept: text/html"`.
+    ///
+    /// An error is returned if there is no `:`, or the name or value are invalid.
+    pub fn try_raw_header(self, raw_header: &str) -> Result<Self> {
+        let (name, value) = raw_header
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Expected a header of the form 'Name: value'"))?;
+
+        let header_name = HeaderName::try_from(name.trim()).context("Invalid header name")?;
+        let header_value = HeaderValue::try_from(value.trim())
+            .with_context(|| format!("Invalid value for header '{}'", header_name))?;
+
+        Ok(self.add_header(header_name, header_value))
+    }
+
+    /// Adds all of the headers given to this request.
+    ///
+    /// Like `Request::add_header`, headers with the same name
+    /// are all sent, rather than replacing each other.
+    pub fn add_headers<I, N, V>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = (N, V)>,
+        N: TryInto<HeaderName>,
+        N::Error: Into<HttpError>,
+        V: TryInto<HeaderValue>,
+        V::Error: Into<HttpError>,
+    {
+        for (name, value) in headers {
+            self = self.add_header(name, value);
+        }
+
+        self
+    }
+
+    /// Removes all headers set on this request,
+    /// including any default headers from the `Server`.
+    pub fn clear_headers(mut self) -> Self {
+        self.config.default_headers.clear();
+        self.headers.clear();
+        self
+    }
+
+    /// Sets the `Authorization` header to use HTTP Basic authentication,
+    /// with the username and optional password given.
+    ///
+    /// This replaces any `Authorization` header already set.
+    pub fn basic_auth(self, username: &str, password: Option<&str>) -> Self {
+        let credentials = format!("{}:{}", username, password.unwrap_or_default());
+        let header_value = format!("Basic {}", BASE64.encode(credentials));
+
+        self.authorization(header_value)
+    }
+
+    /// Sets the `Authorization` header to use the bearer token given.
+    ///
+    /// This replaces any `Authorization` header already set.
+    pub fn bearer_token(self, token: &str) -> Self {
+        let header_value = format!("Bearer {}", token);
+
+        self.authorization(header_value)
+    }
+
+    fn authorization(mut self, header_value: String) -> Self {
+        self.headers
+            .retain(|(name, _)| name != header::AUTHORIZATION);
+        self.add_header(header::AUTHORIZATION, header_value)
+    }
+
+    /// Sets the `Host` header to the value given.
+    ///
+    /// The request is still sent to the address of the `Server`.
+    /// This is useful for testing apps which sit behind a reverse proxy.
+    ///
+    /// Calling this multiple times will replace the previous host.
+    pub fn host(mut self, host: &str) -> Self {
+        self.headers.retain(|(name, _)| name != header::HOST);
+        self.add_header(header::HOST, host)
+    }
+
+    /// The `Host` header will not be added automatically.
+    ///
+    /// This is useful for testing how an app handles requests without a host.
+    /// A `Host` header can still be set using `Request::host`.
+    /// This only applies to HTTP/1, as HTTP/2 always sends the host within the url.
+    pub fn no_host_header(mut self) -> Self {
+        self.is_sending_host_header = false;
+        self
+    }
+
+    /// Sets the `If-None-Match` header, to the ETag given.
+    ///
+    /// The ETag should include its quotes, i.e. `"abc123"`.
+    pub fn if_none_match(self, etag: &str) -> Self {
+        self.add_header(header::IF_NONE_MATCH, etag)
+    }
+
+    /// Sets the `If-Modified-Since` header, to the HTTP date given.
+    /// i.e. `"Wed, 21 Oct 2015 07:28:00 GMT"`.
+    pub fn if_modified_since(self, date: &str) -> Self {
+        self.add_header(header::IF_MODIFIED_SINCE, date)
+    }
+
+    /// Sets the `Accept` header, for the mime type given.
+    pub fn accept(self, mime: &str) -> Self {
+        self.add_header(header::ACCEPT, mime)
+    }
+
+    /// Sets the `User-Agent` header to the value given.
+    pub fn user_agent(self, user_agent: &str) -> Self {
+        self.add_header(header::USER_AGENT, user_agent)
+    }
+
+    /// Adds the segment given onto the end of the request path,
+    /// with exactly one `/` between them.
+    ///
+    /// Any query parameters already on the request are kept.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::get;
+    /// # use ::kantan::Server;
+    /// #
+    /// # let app = Router::new().route("/users/123/posts", get(|| async {}));
+    /// # let server = Server::new_with_router(app).unwrap();
+    /// // Requests `/users/123/posts`
+    /// let response = server.get("/users").append_path("123").append_path("/posts").await;
+    /// # })
+    /// ```
+    pub fn append_path(mut self, segment: &str) -> Self {
+        self.details.request_path = append_path(&self.details.request_path, segment)
+            .with_context(|| {
+                format!(
+                    "Failed to append path '{}' onto {}",
+                    segment, self.details.request_path
+                )
+            })
+            .unwrap();
+
+        self
+    }
+
+    /// Adds a query parameter to the end of the request url.
+    ///
+    /// Calling this multiple times will add each parameter in turn.
+    pub fn query_param(self, key: &str, value: &str) -> Self {
+        self.query(&[(key, value)])
+    }
+
+    /// Adds all of the query parameters given to the end of the request url,
+    /// in order.
+    ///
+    /// Keys can be repeated, which is useful for array style parameters.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::get;
+    /// # use ::kantan::Server;
+    /// #
+    /// # let app = Router::new().route("/users", get(|| async {}));
+    /// # let server = Server::new_with_router(app).unwrap();
+    /// // Requests `/users?id=1&id=2`
+    /// let response = server.get("/users").query_params([("id", "1"), ("id", "2")]).await;
+    /// # })
+    /// ```
+    pub fn query_params<I, K, V>(self, params: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let params: Vec<(K, V)> = params.into_iter().collect();
+        let params: Vec<(&str, &str)> = params
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.as_ref()))
+            .collect();
+
+        self.query(&params)
+    }
+
+    /// Serializes the given value into a query string,
+    /// and adds it to the end of the request url.
+    ///
+    /// This is added alongside any existing query parameters,
+    /// including any that were in the original path.
+    pub fn query<T>(mut self, params: &T) -> Self
+    where
+        T: ?Sized + Serialize,
+    {
+        let query = ::serde_urlencoded::to_string(params)
+            .context("It should serialize the content into a query string")
+            .unwrap();
+
+        self.details.request_path = append_query(&self.details.request_path, &query)
+            .with_context(|| {
+                format!(
+                    "Failed to add query '{}' to request {}",
+                    query, self.details.request_path
+                )
+            })
+            .unwrap();
+
+        self
+    }
+
     /// Set the body of the request to send up as Json.
-    pub fn json<J>(mut self, body: &J) -> Self
+    ///
+    /// This will panic if the body cannot be serialised into JSON.
+    /// See `Request::try_json` for a version which returns the error.
+    pub fn json<J>(self, body: &J) -> Self
+    where
+        J: ?Sized + Serialize,
+    {
+        self.try_json(body)
+            .context("It should serialize the content into JSON")
+            .unwrap()
+    }
+
+    /// Set the body of the request to send up as Json.
+    ///
+    /// An error is returned if the body cannot be serialised into JSON.
+    pub fn try_json<J>(mut self, body: &J) -> Result<Self>
     where
         J: ?Sized + Serialize,
     {
-        let body_bytes = json_to_vec(body).expect("It should serialize the content into JSON");
+        let body_bytes = json_to_vec(body)?;
         let body: Body = body_bytes.into();
         self.body = Some(body);
 
-        if self.config.content_type == None {
+        if self.config.content_type.is_none() {
+            self.config.content_type = Some(JSON_CONTENT_TYPE.to_string());
+        }
+
+        Ok(self)
+    }
+
+    /// Set the body of the request to send up as Json, from the `Value` given.
+    ///
+    /// This is useful with values built using the `serde_json::json!` macro.
+    /// If there isn't a content type set, this will default to `application/json`.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::post;
+    /// # use ::kantan::Server;
+    /// use ::serde_json::json;
+    /// #
+    /// # let app = Router::new().route("/users", post(|| async {}));
+    /// # let server = Server::new_with_router(app).unwrap();
+    ///
+    /// let response = server
+    ///     .post("/users")
+    ///     .json_value(json!({ "name": "Joe" }))
+    ///     .await;
+    /// # })
+    /// ```
+    pub fn json_value(self, body: Value) -> Self {
+        self.json(&body)
+    }
+
+    /// Set the body of the request to send up as pretty printed Json.
+    ///
+    /// This is useful when the server checks the exact bytes sent,
+    /// such as verifying a signature over the body.
+    ///
+    /// If there isn't a content type set, this will default to `application/json`.
+    pub fn json_pretty<J>(mut self, body: &J) -> Self
+    where
+        J: ?Sized + Serialize,
+    {
+        let body_bytes = json_to_vec_pretty(body)
+            .context("It should serialize the content into pretty JSON")
+            .unwrap();
+        let body: Body = body_bytes.into();
+        self.body = Some(body);
+
+        if self.config.content_type.is_none() {
             self.config.content_type = Some(JSON_CONTENT_TYPE.to_string());
         }
 
         self
     }
 
+    /// Set the body of the request to send up as a JSON Merge Patch,
+    /// as described in RFC 7396.
+    ///
+    /// This serialises the body as JSON, and will always set the content type
+    /// to `application/merge-patch+json`.
+    pub fn json_merge_patch<J>(mut self, body: &J) -> Self
+    where
+        J: ?Sized + Serialize,
+    {
+        self.config.content_type = Some(JSON_MERGE_PATCH_CONTENT_TYPE.to_string());
+
+        self.json(body)
+    }
+
+    /// Set the body of the request to send up as a url encoded form.
+    ///
+    /// If there isn't a content type set, this will default to
+    /// `application/x-www-form-urlencoded`.
+    pub fn form<T>(mut self, body: &T) -> Self
+    where
+        T: ?Sized + Serialize,
+    {
+        let body_text = ::serde_urlencoded::to_string(body)
+            .context("It should serialize the content into a form")
+            .unwrap();
+        let body_bytes = Bytes::from(body_text.into_bytes());
+
+        if self.config.content_type.is_none() {
+            self.config.content_type = Some(FORM_CONTENT_TYPE.to_string());
+        }
+
+        self.bytes(body_bytes)
+    }
+
+    /// Set the body of the request to send up as a `multipart/form-data` form.
+    ///
+    /// This will always set the content type,
+    /// as it must include the boundary used by the form.
+    pub fn multipart(mut self, form: MultipartForm) -> Self {
+        self.config.content_type = Some(form.content_type());
+
+        self.bytes(form.into_bytes())
+    }
+
     /// Set raw text as the body of the request.
     ///
     /// If there isn't a content type set, this will default to `text/plain`.
-    pub fn text<T>(mut self, raw_text: T) -> Self
+    pub fn text<T>(self, raw_text: T) -> Self
     where
         T: Display,
     {
-        let body_text = format!("{}", raw_text);
+        // `to_string` avoids the formatting machinery for string types.
+        self.text_string(raw_text.to_string())
+    }
+
+    /// Set raw text as the body of the request, taking ownership of the text.
+    ///
+    /// This is the same as `Request::text`, however an owned `String`
+    /// is used as the body without being copied.
+    ///
+    /// If there isn't a content type set, this will default to `text/plain`.
+    pub fn text_string<T>(mut self, raw_text: T) -> Self
+    where
+        T: Into<String>,
+    {
+        let body_text: String = raw_text.into();
         let body_bytes = Bytes::from(body_text.into_bytes());
 
-        if self.config.content_type == None {
+        if self.config.content_type.is_none() {
             self.config.content_type = Some(TEXT_CONTENT_TYPE.to_string());
         }
 
@@ -179,71 +922,413 @@ impl Request {
         self
     }
 
+    /// Set the body of the request to the `Body` given.
+    ///
+    /// This is sent as is, and the content type is left unchanged.
+    pub fn body(mut self, body: Body) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Sets trailing headers to be sent after the body of the request.
+    ///
+    /// This will send the body as a stream, without a `Content-Length`.
+    /// Note that trailers are only sent when using HTTP/2,
+    /// such as with `Request::http2_prior_knowledge`.
+    pub fn trailers(mut self, trailers: HeaderMap) -> Self {
+        self.trailers = Some(trailers);
+        self
+    }
+
+    /// Sends the body using `Transfer-Encoding: chunked`,
+    /// rather than with a `Content-Length`.
+    ///
+    /// This is useful for testing how a server handles bodies
+    /// where the size is not known up front.
+    /// Note that chunked encoding only exists in HTTP/1.1.
+    pub fn chunked(mut self) -> Self {
+        self.is_chunked = true;
+        self
+    }
+
+    /// Set a stream as the body of the request.
+    ///
+    /// The body is sent as it is read from the stream,
+    /// rather than being held in memory first.
+    /// This is useful for testing endpoints which take very large uploads.
+    ///
+    /// If following redirects, then the stream will be read into memory
+    /// before the request is sent. The content type is left unchanged.
+    pub fn stream_body<S>(mut self, stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes>> + Send + 'static,
+    {
+        let body = Body::wrap_stream(stream);
+        self.body = Some(body);
+
+        self
+    }
+
     /// Set the content type to use for this request in the header.
+    ///
+    /// Any whitespace around the content type is removed.
+    /// The case is kept as given.
     pub fn content_type(mut self, content_type: &str) -> Self {
-        self.config.content_type = Some(content_type.to_string());
+        self.config.content_type = Some(content_type.trim().to_string());
         self
     }
 
     async fn send_or_panic(self) -> Response {
-        self.send().await.expect("Sending request failed")
+        let expected_failure = self.expected_failure.clone();
+        let expected_status = self.expected_status.clone();
+        let request_path = self.details.request_path.clone();
+
+        match self.send().await {
+            Ok(response) if expected_failure.is_some() => {
+                panic!(
+                    "Expected request to {} to fail, but it succeeded with status {}",
+                    request_path,
+                    response.status_code()
+                );
+            }
+            Ok(response) => {
+                if let Some(expected_status) = expected_status {
+                    assert_expected_status(&response, &expected_status);
+                }
+
+                response
+            }
+            Err(err) => match expected_failure {
+                Some(expected_failure) if expected_failure.is_match(&err) => {
+                    Response::new_transport_failure(request_path, err)
+                }
+                Some(expected_failure) => {
+                    panic!(
+                        "Expected request to {} to {}, but it failed with {:?}",
+                        request_path, expected_failure, err
+                    );
+                }
+                None => {
+                    panic!("Sending request to {} failed, {:?}", request_path, err);
+                }
+            },
+        }
     }
 
-    async fn send(mut self) -> Result<Response> {
-        let request_path = self.config.request_path;
-        let method = self.config.method;
-        let content_type = self.config.content_type;
-        let save_cookies = self.is_saving_cookies;
-        let body = self.body.unwrap_or(Body::empty());
+    /// Asks the server to compress the response, when it will be decompressed.
+    ///
+    /// Responses are only decompressed by default when this sends the `Accept-Encoding`,
+    /// as otherwise the caller asked for the compression and may want to see it.
+    fn add_accept_encoding_header(&mut self) {
+        let has_accept_encoding = self
+            .headers
+            .iter()
+            .any(|(name, _)| name == header::ACCEPT_ENCODING);
+
+        if self.is_decompressing != Some(false) && !has_accept_encoding {
+            self.headers.push((
+                header::ACCEPT_ENCODING,
+                HeaderValue::from_static(ACCEPT_ENCODING),
+            ));
+            self.is_decompressing.get_or_insert(true);
+        }
+    }
 
-        let mut request_builder = HyperRequest::builder().uri(&request_path).method(method);
+    /// Builds the Hyper request which would be sent, without sending it.
+    /// This includes the full url, the headers, the cookies, and the body.
+    ///
+    /// This is useful for checking what a `Request` will send.
+    /// Redirects, retries, and status checks are not applied.
+    pub fn into_hyper_request(mut self) -> Result<HyperRequest<Body>> {
+        self.prepare_to_send();
+
+        let has_body = self.body.is_some();
+        let body = self.body.take().unwrap_or(Body::empty());
+
+        self.build_request(
+            &self.details.method,
+            &self.details.request_path,
+            self.config.content_type.as_deref(),
+            has_body,
+            body,
+        )
+    }
+
+    /// Sets up anything which is applied once, before the first request is sent.
+    fn prepare_to_send(&mut self) {
+        if self.is_scoping_cookies {
+            let mut scoped_cookies = CookieJar::new();
+            for cookie in self.cookies.iter() {
+                let mut cookie = cookie.clone().into_owned();
+                scope_cookie(&mut cookie, &self.details.request_path);
+                scoped_cookies.add(cookie);
+            }
 
-        // Add all the headers we have.
-        let mut headers = self.headers;
-        if let Some(content_type) = content_type {
-            let header = build_content_type_header(content_type)?;
-            headers.push(header);
+            self.cookies = scoped_cookies;
         }
 
-        // Add all the cookies as headers
-        for cookie in self.cookies.iter() {
-            let cookie_raw = cookie.to_string();
-            let header_value = HeaderValue::from_str(&cookie_raw)?;
-            headers.push((header::COOKIE, header_value));
+        self.add_accept_encoding_header();
+    }
+
+    /// Builds the Hyper request to send, with the headers, cookies,
+    /// and settings from this `Request`.
+    ///
+    /// This is used for every request sent, including redirects and retries.
+    fn build_request(
+        &self,
+        method: &Method,
+        request_path: &Uri,
+        content_type: Option<&str>,
+        has_body: bool,
+        mut body: Body,
+    ) -> Result<HyperRequest<Body>> {
+        if let Some(trailers) = self.trailers.clone() {
+            body = body_with_trailers(body, trailers);
         }
 
-        // Put headers into the request
-        for (header_name, header_value) in headers {
-            request_builder = request_builder.header(header_name, header_value);
+        // Wrapping the body as a stream hides its size,
+        // so Hyper falls back to sending it chunked.
+        if self.is_chunked && has_body {
+            body = Body::wrap_stream(body);
         }
 
-        let request = request_builder.body(body).with_context(|| {
-            format!(
-                "Expect valid hyper Request to be built on request to {}",
-                request_path
-            )
-        })?;
+        // Buffered bodies have an exact size, streamed bodies do not.
+        let content_length = if has_body {
+            HttpBody::size_hint(&body).exact()
+        } else {
+            None
+        };
 
-        let https = HttpsConnector::new();
-        let client = Client::builder().build::<_, hyper::Body>(https);
+        let no_cookies = CookieJar::new();
+        let cookies = if self.is_sending_cookies {
+            &self.cookies
+        } else {
+            &no_cookies
+        };
 
-        let hyper_response = client.request(request).await.with_context(|| {
-            format!(
-                "Expect Hyper Response to succeed on request to {}",
-                request_path
-            )
-        })?;
+        let request_builder = build_hyper_request_builder(
+            method,
+            request_path,
+            content_type,
+            content_length,
+            &self.headers,
+            cookies,
+        )?;
+        let mut request =
+            build_hyper_request(request_builder, &self.request_mappers, request_path, body)?;
+        if let Some(http_version) = self.http_version {
+            *request.version_mut() = http_version;
+        }
+
+        Ok(request)
+    }
+
+    /// Sends the request, returning an error if it could not be sent.
+    ///
+    /// This is an alternative to awaiting the `Request` directly,
+    /// which will panic on errors. Errors include failing to connect
+    /// to the server, failing to read the response, or timing out.
+    ///
+    /// The checks from `Request::expect_failure`, `Request::expect_success`,
+    /// and `Request::expect_status` are not applied.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::kantan::Server;
+    /// # use ::std::net::TcpListener;
+    /// #
+    /// # // A port with nothing listening on it.
+    /// # let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    /// # let server = Server::new(format!("http://127.0.0.1:{}", port)).unwrap();
+    /// let result = server.get("/ping").send().await;
+    /// assert!(result.is_err());
+    /// # })
+    /// ```
+    pub async fn send(self) -> Result<Response> {
+        let maybe_timeout = self.config.timeout;
+        let request_path = self.details.request_path.clone();
+
+        match maybe_timeout {
+            Some(duration) => timeout(duration, self.send_without_timeout())
+                .await
+                .map_err(|err| {
+                    Error::new(err).context(format!(
+                        "Request to {} timed out after {:?}",
+                        request_path, duration
+                    ))
+                })?,
+            None => self.send_without_timeout().await,
+        }
+    }
+
+    async fn send_without_timeout(mut self) -> Result<Response> {
+        self.prepare_to_send();
 
-        let (parts, response_body) = hyper_response.into_parts();
-        let response_bytes = to_bytes(response_body).await?;
+        let mut request_path = self.details.request_path.clone();
+        let mut method = self.details.method.clone();
+        let mut content_type = self.config.content_type.clone();
+        let save_cookies = self.is_saving_cookies;
+        let is_decompressing = self.is_decompressing.unwrap_or(false);
+        let mut redirects_remaining = self.max_redirects;
+        let mut retries_remaining = self.max_retries;
+        let mut has_body = self.body.is_some();
+        let mut body = self.body.take().unwrap_or(Body::empty());
 
-        if save_cookies {
-            let cookie_headers = parts.headers.get_all(SET_COOKIE).into_iter();
-            InnerServer::add_cookies_by_header(&mut self.inner_test_server, cookie_headers)?;
+        // When following redirects or retrying the body is held onto, so it can be sent again.
+        // It is also held when debugging, so it can be logged.
+        let mut maybe_body_bytes = None;
+        if redirects_remaining > 0 || retries_remaining > 0 || self.is_debugging {
+            let body_bytes = to_bytes(body)
+                .await
+                .context("Reading request body for sending")?;
+            body = Body::from(body_bytes.clone());
+            maybe_body_bytes = Some(body_bytes);
         }
 
-        let response = Response::new(request_path, parts, response_bytes);
-        Ok(response)
+        loop {
+            let request = self.build_request(
+                &method,
+                &request_path,
+                content_type.as_deref(),
+                has_body,
+                body,
+            )?;
+
+            if self.is_debugging {
+                let body_bytes = maybe_body_bytes.clone().unwrap_or_default();
+                log_request(&request, &body_bytes, self.is_debugging_unredacted);
+            }
+
+            let request_start = Instant::now();
+            let hyper_response = match self
+                .client
+                .request(
+                    request,
+                    self.is_http2_prior_knowledge,
+                    self.is_sending_host_header,
+                )
+                .await
+            {
+                Err(err) if err.is_connect() && retries_remaining > 0 => {
+                    retries_remaining -= 1;
+                    sleep(self.retry_delay).await;
+
+                    body = maybe_body_bytes
+                        .clone()
+                        .map(Body::from)
+                        .unwrap_or(Body::empty());
+                    continue;
+                }
+                result => result.with_context(|| {
+                    format!(
+                        "Expect Hyper Response to succeed on request to {}",
+                        request_path
+                    )
+                })?,
+            };
+
+            let (mut parts, response_body) = hyper_response.into_parts();
+            let (response_bytes, response_trailers) =
+                read_body_with_trailers(response_body, self.max_response_size)
+                    .await
+                    .with_context(|| format!("Reading response body from {}", request_path))?;
+            let duration = request_start.elapsed();
+
+            if self.is_debugging {
+                log_response(
+                    &request_path,
+                    parts.status,
+                    &parts.headers,
+                    &response_bytes,
+                    self.is_debugging_unredacted,
+                );
+            }
+
+            if save_cookies {
+                let cookie_headers = parts.headers.get_all(SET_COOKIE).into_iter();
+                InnerServer::add_cookies_by_header(&mut self.inner_test_server, cookie_headers)?;
+                InnerServer::prune_expired_cookies(&mut self.inner_test_server)?;
+            }
+
+            let maybe_location = parts.headers.get(header::LOCATION);
+            let location = match maybe_location {
+                Some(location) if parts.status.is_redirection() && redirects_remaining > 0 => {
+                    location.to_str().with_context(|| {
+                        format!("Reading redirect location for request to {}", request_path)
+                    })?
+                }
+                _ => {
+                    let response_bytes = if is_decompressing {
+                        decompress(
+                            &method,
+                            &mut parts.headers,
+                            response_bytes,
+                            self.max_response_size,
+                        )
+                        .with_context(|| format!("Decompressing response to {}", request_path))?
+                    } else {
+                        response_bytes
+                    };
+
+                    let response = Response::new(
+                        request_path,
+                        parts,
+                        response_bytes,
+                        response_trailers,
+                        duration,
+                    );
+                    return Ok(response);
+                }
+            };
+            redirects_remaining -= 1;
+
+            // Cookies set along the way are sent on to the next request.
+            for cookie_header in parts.headers.get_all(SET_COOKIE) {
+                let cookie_header_str = cookie_header
+                    .to_str()
+                    .context("Reading cookie header when following redirect")?;
+                let cookie = Cookie::parse(cookie_header_str)?.into_owned();
+                self.cookies.add(cookie);
+            }
+
+            // Like a browser, a 303 always becomes a GET,
+            // and a 301 or 302 becomes a GET when it was a POST.
+            let is_see_other = parts.status == StatusCode::SEE_OTHER && method != Method::HEAD;
+            let is_moved_post = (parts.status == StatusCode::MOVED_PERMANENTLY
+                || parts.status == StatusCode::FOUND)
+                && method == Method::POST;
+            if is_see_other || is_moved_post {
+                method = Method::GET;
+                content_type = None;
+                has_body = false;
+                maybe_body_bytes = None;
+            }
+
+            let next_request_path = resolve_location(&request_path, location)?;
+
+            // Credentials are not passed on to a different host, like a browser.
+            if next_request_path.authority() != request_path.authority() {
+                self.headers
+                    .retain(|(name, _)| name != header::AUTHORIZATION);
+
+                let host_less_cookies: Vec<Cookie<'static>> = self
+                    .cookies
+                    .iter()
+                    .filter(|cookie| cookie.domain().is_none())
+                    .cloned()
+                    .collect();
+                for cookie in host_less_cookies {
+                    self.cookies.force_remove(&cookie);
+                }
+            }
+
+            request_path = next_request_path;
+
+            body = maybe_body_bytes
+                .clone()
+                .map(Body::from)
+                .unwrap_or(Body::empty());
+        }
     }
 }
 
@@ -259,9 +1344,145 @@ impl IntoFuture for Request {
     }
 }
 
+fn build_hyper_request_builder(
+    method: &Method,
+    request_path: &Uri,
+    content_type: Option<&str>,
+    content_length: Option<u64>,
+    headers: &[(HeaderName, HeaderValue)],
+    cookies: &CookieJar,
+) -> Result<RequestBuilder> {
+    let mut request_builder = HyperRequest::builder()
+        .uri(request_path)
+        .method(method.clone());
+
+    // Add all the headers we have.
+    let mut headers = headers.to_vec();
+    if let Some(content_type) = content_type {
+        let header = build_content_type_header(content_type.to_string())?;
+        headers.push(header);
+    }
+
+    // Content-Length is set explicitly, unless it was already given.
+    let has_content_length = headers
+        .iter()
+        .any(|(name, _)| name == header::CONTENT_LENGTH);
+    if let (Some(content_length), false) = (content_length, has_content_length) {
+        headers.push((header::CONTENT_LENGTH, HeaderValue::from(content_length)));
+    }
+
+    // Add all the cookies as headers, which match the domain and path and have not expired.
+    for cookie in cookies
+        .iter()
+        .filter(|cookie| is_cookie_matching(cookie, request_path))
+        .filter(|cookie| !is_cookie_expired(cookie))
+    {
+        let cookie_raw = cookie.stripped().to_string();
+        let header_value = HeaderValue::from_str(&cookie_raw)?;
+        headers.push((header::COOKIE, header_value));
+    }
+
+    // Put headers into the request
+    for (header_name, header_value) in headers {
+        request_builder = request_builder.header(header_name, header_value);
+    }
+
+    Ok(request_builder)
+}
+
+/// Applies the mappers to the builder, and then adds the body.
+fn build_hyper_request(
+    request_builder: RequestBuilder,
+    request_mappers: &[RequestMapper],
+    request_path: &Uri,
+    body: Body,
+) -> Result<HyperRequest<Body>> {
+    let request_builder = request_mappers
+        .iter()
+        .fold(request_builder, |request_builder, request_mapper| {
+            request_mapper.apply(request_builder)
+        });
+
+    let request = request_builder.body(body).with_context(|| {
+        format!(
+            "Expect valid hyper Request to be built on request to {}",
+            request_path
+        )
+    })?;
+
+    Ok(request)
+}
+
 fn build_content_type_header(content_type: String) -> Result<(HeaderName, HeaderValue)> {
     let header_value = HeaderValue::from_str(&content_type)
         .with_context(|| format!("Failed to store header content type '{}'", content_type))?;
 
     Ok((header::CONTENT_TYPE, header_value))
 }
+
+fn append_query(request_path: &Uri, query: &str) -> Result<Uri> {
+    if query.is_empty() {
+        return Ok(request_path.clone());
+    }
+
+    let separator = match request_path.query() {
+        None => "?",
+        Some("") => "",
+        Some(_) => "&",
+    };
+
+    let full_path = format!("{}{}{}", request_path, separator, query).try_into()?;
+    Ok(full_path)
+}
+
+fn append_path(request_path: &Uri, segment: &str) -> Result<Uri> {
+    let path = encode_path(&join_paths(&[request_path.path(), segment]));
+    let path_and_query = match request_path.query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path,
+    };
+
+    let mut parts = request_path.clone().into_parts();
+    parts.path_and_query = Some(path_and_query.try_into()?);
+
+    Ok(Uri::from_parts(parts)?)
+}
+
+/// Works out where a redirect is going to,
+/// from the location given relative to the current request.
+pub(crate) fn resolve_location(request_path: &Uri, location: &str) -> Result<Uri> {
+    let location_uri: Uri = location
+        .try_into()
+        .with_context(|| format!("Failed to parse redirect location '{}'", location))?;
+    if location_uri.scheme().is_some() {
+        return Ok(location_uri);
+    }
+
+    let scheme = request_path.scheme_str().unwrap_or("http");
+    let authority = request_path
+        .authority()
+        .with_context(|| format!("Request {} is missing a host", request_path))?;
+
+    if location.starts_with('/') {
+        let full_path = format!("{}://{}{}", scheme, authority, location).try_into()?;
+        return Ok(full_path);
+    }
+
+    let path = request_path.path();
+    let directory = &path[..path.rfind('/').map(|i| i + 1).unwrap_or(0)];
+    let full_path = format!("{}://{}{}{}", scheme, authority, directory, location).try_into()?;
+    Ok(full_path)
+}
+
+fn assert_expected_status(response: &Response, expected_status: &ExpectedStatus) {
+    let status_code = response.status_code();
+
+    assert!(
+        expected_status.is_match(status_code),
+        "Expected {} for request {}, received {}, with body '{}'",
+        expected_status,
+        response.request_uri(),
+        status_code,
+        response.status_body_snippet()
+    );
+}
@@ -0,0 +1,326 @@
+use ::cookie::time::Duration;
+use ::cookie::time::OffsetDateTime;
+use ::cookie::Cookie;
+use ::hyper::http::Uri;
+
+/// Returns true if the cookie should be sent on a request to the path given.
+///
+/// This checks the cookie's `Domain` and `Path` against the request,
+/// following the rules in RFC 6265. Cookies without a `Domain` or `Path`
+/// will match all requests.
+pub(crate) fn is_cookie_matching(cookie: &Cookie, request_path: &Uri) -> bool {
+    is_domain_matching(cookie.domain(), request_path.host())
+        && is_path_matching(cookie.path(), request_path.path())
+}
+
+/// Returns true if the cookie has expired, and so should no longer be sent.
+///
+/// When a cookie has both a `Max-Age` and an `Expires`, then the `Max-Age` is used.
+pub(crate) fn is_cookie_expired(cookie: &Cookie) -> bool {
+    if let Some(max_age) = cookie.max_age() {
+        return max_age <= Duration::ZERO;
+    }
+
+    cookie
+        .expires_datetime()
+        .is_some_and(|expires| expires <= OffsetDateTime::now_utc())
+}
+
+/// Replaces a positive `Max-Age` with an `Expires` of that long from now.
+///
+/// A `Max-Age` is relative to when the cookie was received,
+/// so this is needed for stored cookies to later expire.
+pub(crate) fn expire_from_max_age(cookie: &mut Cookie) {
+    if let Some(max_age) = cookie.max_age() {
+        if max_age > Duration::ZERO {
+            cookie.set_expires(OffsetDateTime::now_utc() + max_age);
+            cookie.set_max_age(None);
+        }
+    }
+}
+
+/// Sets the `Domain` and `Path` of the cookie from the request,
+/// when they are not already set. This is how a browser scopes cookies.
+///
+/// The default path is the directory of the request path, following RFC 6265.
+pub(crate) fn scope_cookie(cookie: &mut Cookie<'static>, request_path: &Uri) {
+    if cookie.domain().is_none() {
+        if let Some(host) = request_path.host() {
+            cookie.set_domain(host.to_string());
+        }
+    }
+
+    if cookie.path().is_none() {
+        cookie.set_path(default_cookie_path(request_path.path()).to_string());
+    }
+}
+
+fn default_cookie_path(request_path: &str) -> &str {
+    if !request_path.starts_with('/') {
+        return "/";
+    }
+
+    match request_path.rfind('/') {
+        Some(0) | None => "/",
+        Some(last_slash) => &request_path[..last_slash],
+    }
+}
+
+fn is_domain_matching(maybe_cookie_domain: Option<&str>, maybe_host: Option<&str>) -> bool {
+    let cookie_domain = match maybe_cookie_domain {
+        Some(cookie_domain) => cookie_domain.trim_start_matches('.'),
+        None => return true,
+    };
+    let host = match maybe_host {
+        Some(host) => host,
+        None => return false,
+    };
+
+    if host.eq_ignore_ascii_case(cookie_domain) {
+        return true;
+    }
+
+    // The host can be a subdomain of the cookie domain.
+    host.len() > cookie_domain.len()
+        && host[host.len() - cookie_domain.len()..].eq_ignore_ascii_case(cookie_domain)
+        && host.as_bytes()[host.len() - cookie_domain.len() - 1] == b'.'
+}
+
+fn is_path_matching(maybe_cookie_path: Option<&str>, request_path: &str) -> bool {
+    let cookie_path = match maybe_cookie_path {
+        Some(cookie_path) if cookie_path.starts_with('/') => cookie_path,
+        _ => return true,
+    };
+
+    if !request_path.starts_with(cookie_path) {
+        return false;
+    }
+
+    // `/admin` matches `/admin` and `/admin/users`, but not `/administrator`.
+    cookie_path.ends_with('/')
+        || request_path.len() == cookie_path.len()
+        || request_path.as_bytes()[cookie_path.len()] == b'/'
+}
+
+#[cfg(test)]
+mod test_is_cookie_matching {
+    use super::*;
+
+    fn is_matching(cookie_str: &str, request_path: &str) -> bool {
+        let cookie = Cookie::parse(cookie_str).unwrap();
+        let request_path: Uri = request_path.parse().unwrap();
+
+        is_cookie_matching(&cookie, &request_path)
+    }
+
+    #[test]
+    fn it_should_match_cookie_without_domain_or_path() {
+        assert!(is_matching("name=value", "http://example.com/users"));
+    }
+
+    #[test]
+    fn it_should_match_cookie_on_same_path() {
+        assert!(is_matching(
+            "name=value; Path=/admin",
+            "http://example.com/admin"
+        ));
+    }
+
+    #[test]
+    fn it_should_match_cookie_on_sub_path() {
+        assert!(is_matching(
+            "name=value; Path=/admin",
+            "http://example.com/admin/users"
+        ));
+        assert!(is_matching(
+            "name=value; Path=/admin/",
+            "http://example.com/admin/users"
+        ));
+    }
+
+    #[test]
+    fn it_should_not_match_cookie_on_other_path() {
+        assert!(!is_matching(
+            "name=value; Path=/admin",
+            "http://example.com/public"
+        ));
+    }
+
+    #[test]
+    fn it_should_not_match_cookie_on_path_with_same_prefix() {
+        assert!(!is_matching(
+            "name=value; Path=/admin",
+            "http://example.com/administrator"
+        ));
+    }
+
+    #[test]
+    fn it_should_match_cookie_on_same_domain() {
+        assert!(is_matching(
+            "name=value; Domain=example.com",
+            "http://example.com/users"
+        ));
+        assert!(is_matching(
+            "name=value; Domain=EXAMPLE.com",
+            "http://example.COM/users"
+        ));
+    }
+
+    #[test]
+    fn it_should_match_cookie_on_sub_domain() {
+        assert!(is_matching(
+            "name=value; Domain=example.com",
+            "http://api.example.com/users"
+        ));
+        assert!(is_matching(
+            "name=value; Domain=.example.com",
+            "http://api.example.com/users"
+        ));
+    }
+
+    #[test]
+    fn it_should_not_match_cookie_on_other_domain() {
+        assert!(!is_matching(
+            "name=value; Domain=example.com",
+            "http://other.com/users"
+        ));
+        assert!(!is_matching(
+            "name=value; Domain=example.com",
+            "http://notexample.com/users"
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_is_cookie_expired {
+    use super::*;
+
+    #[test]
+    fn it_should_not_be_expired_without_expiry() {
+        let cookie = Cookie::new("name", "value");
+
+        assert!(!is_cookie_expired(&cookie));
+    }
+
+    #[test]
+    fn it_should_be_expired_with_zero_max_age() {
+        let cookie = Cookie::parse("name=value; Max-Age=0").unwrap();
+
+        assert!(is_cookie_expired(&cookie));
+    }
+
+    #[test]
+    fn it_should_not_be_expired_with_future_max_age() {
+        let cookie = Cookie::parse("name=value; Max-Age=3600").unwrap();
+
+        assert!(!is_cookie_expired(&cookie));
+    }
+
+    #[test]
+    fn it_should_be_expired_with_past_expires() {
+        let cookie = Cookie::parse("name=value; Expires=Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+
+        assert!(is_cookie_expired(&cookie));
+    }
+
+    #[test]
+    fn it_should_not_be_expired_with_future_expires() {
+        let mut cookie = Cookie::new("name", "value");
+        cookie.set_expires(OffsetDateTime::now_utc() + Duration::hours(1));
+
+        assert!(!is_cookie_expired(&cookie));
+    }
+
+    #[test]
+    fn it_should_prefer_max_age_over_expires() {
+        let cookie =
+            Cookie::parse("name=value; Max-Age=3600; Expires=Wed, 21 Oct 2015 07:28:00 GMT")
+                .unwrap();
+
+        assert!(!is_cookie_expired(&cookie));
+    }
+}
+
+#[cfg(test)]
+mod test_expire_from_max_age {
+    use super::*;
+
+    #[test]
+    fn it_should_replace_max_age_with_expires() {
+        let mut cookie = Cookie::parse("name=value; Max-Age=3600").unwrap();
+        expire_from_max_age(&mut cookie);
+
+        let expires = cookie.expires_datetime().unwrap();
+        assert_eq!(cookie.max_age(), None);
+        assert!(expires > OffsetDateTime::now_utc() + Duration::minutes(59));
+        assert!(expires <= OffsetDateTime::now_utc() + Duration::hours(1));
+    }
+
+    #[test]
+    fn it_should_leave_zero_max_age_unchanged() {
+        let mut cookie = Cookie::parse("name=value; Max-Age=0").unwrap();
+        expire_from_max_age(&mut cookie);
+
+        assert_eq!(cookie.max_age(), Some(Duration::ZERO));
+        assert_eq!(cookie.expires(), None);
+    }
+
+    #[test]
+    fn it_should_leave_cookie_without_max_age_unchanged() {
+        let mut cookie =
+            Cookie::parse("name=value; Expires=Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        expire_from_max_age(&mut cookie);
+
+        assert!(is_cookie_expired(&cookie));
+    }
+}
+
+#[cfg(test)]
+mod test_scope_cookie {
+    use super::*;
+
+    fn scope(cookie_str: &str, request_path: &str) -> Cookie<'static> {
+        let mut cookie = Cookie::parse(cookie_str).unwrap().into_owned();
+        let request_path: Uri = request_path.parse().unwrap();
+        scope_cookie(&mut cookie, &request_path);
+
+        cookie
+    }
+
+    #[test]
+    fn it_should_set_domain_from_host() {
+        let cookie = scope("name=value", "http://example.com/users");
+
+        assert_eq!(cookie.domain(), Some("example.com"));
+    }
+
+    #[test]
+    fn it_should_keep_domain_already_set() {
+        let cookie = scope("name=value; Domain=other.com", "http://example.com/users");
+
+        assert_eq!(cookie.domain(), Some("other.com"));
+    }
+
+    #[test]
+    fn it_should_set_root_path_for_top_level_request() {
+        assert_eq!(scope("name=value", "http://example.com/").path(), Some("/"));
+        assert_eq!(
+            scope("name=value", "http://example.com/users").path(),
+            Some("/")
+        );
+    }
+
+    #[test]
+    fn it_should_set_directory_path_for_nested_request() {
+        let cookie = scope("name=value", "http://example.com/admin/users/123");
+
+        assert_eq!(cookie.path(), Some("/admin/users"));
+    }
+
+    #[test]
+    fn it_should_keep_path_already_set() {
+        let cookie = scope("name=value; Path=/api", "http://example.com/admin/users");
+
+        assert_eq!(cookie.path(), Some("/api"));
+    }
+}
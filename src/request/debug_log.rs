@@ -0,0 +1,149 @@
+use ::hyper::body::Bytes;
+use ::hyper::header;
+use ::hyper::http::HeaderMap;
+use ::hyper::http::Request as HyperRequest;
+use ::hyper::http::StatusCode;
+use ::hyper::http::Uri;
+use ::hyper::Body;
+
+use crate::truncate_text;
+
+/// The text shown in place of sensitive header values.
+const REDACTED: &str = "[redacted]";
+
+/// Headers which are redacted when logging, as they may contain credentials.
+const REDACTED_HEADERS: [header::HeaderName; 3] =
+    [header::AUTHORIZATION, header::COOKIE, header::SET_COOKIE];
+
+/// Logs the request being sent, along with its body.
+pub(crate) fn log_request(request: &HyperRequest<Body>, body_bytes: &Bytes, is_unredacted: bool) {
+    ::log::debug!("{}", format_request(request, body_bytes, is_unredacted));
+}
+
+/// Logs the response received, for the request sent to the path given.
+pub(crate) fn log_response(
+    request_path: &Uri,
+    status_code: StatusCode,
+    headers: &HeaderMap,
+    body_bytes: &Bytes,
+    is_unredacted: bool,
+) {
+    ::log::debug!(
+        "{}",
+        format_response(
+            request_path,
+            status_code,
+            headers,
+            body_bytes,
+            is_unredacted
+        )
+    );
+}
+
+fn format_request(request: &HyperRequest<Body>, body_bytes: &Bytes, is_unredacted: bool) -> String {
+    format!(
+        "Request {} {} {:?}\n{}\n{}",
+        request.method(),
+        request.uri(),
+        request.version(),
+        format_headers(request.headers(), is_unredacted),
+        truncate_text(&String::from_utf8_lossy(body_bytes))
+    )
+}
+
+fn format_response(
+    request_path: &Uri,
+    status_code: StatusCode,
+    headers: &HeaderMap,
+    body_bytes: &Bytes,
+    is_unredacted: bool,
+) -> String {
+    format!(
+        "Response {} for {}\n{}\n{}",
+        status_code,
+        request_path,
+        format_headers(headers, is_unredacted),
+        truncate_text(&String::from_utf8_lossy(body_bytes))
+    )
+}
+
+fn format_headers(headers: &HeaderMap, is_unredacted: bool) -> String {
+    let mut headers_text = String::new();
+
+    for (header_name, header_value) in headers {
+        let value_text = if !is_unredacted && REDACTED_HEADERS.contains(header_name) {
+            REDACTED.to_string()
+        } else {
+            String::from_utf8_lossy(header_value.as_bytes()).to_string()
+        };
+
+        headers_text.push_str(&format!("{}: {}\n", header_name, value_text));
+    }
+
+    headers_text
+}
+
+#[cfg(test)]
+mod test_format_request {
+    use super::*;
+
+    fn new_request() -> HyperRequest<Body> {
+        HyperRequest::builder()
+            .method("POST")
+            .uri("http://localhost/users")
+            .header(header::AUTHORIZATION, "Bearer my-token")
+            .header(header::COOKIE, "session=abc123")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn it_should_format_request_with_body() {
+        let request = new_request();
+        let body_bytes = Bytes::from(r#"{"name":"Joe"}"#);
+        let output = format_request(&request, &body_bytes, false);
+
+        assert_eq!(
+            output,
+            "Request POST http://localhost/users HTTP/1.1\n\
+             authorization: [redacted]\n\
+             cookie: [redacted]\n\
+             content-type: application/json\n\
+             \n\
+             {\"name\":\"Joe\"}"
+        );
+    }
+
+    #[test]
+    fn it_should_not_redact_headers_when_unredacted() {
+        let request = new_request();
+        let output = format_request(&request, &Bytes::new(), true);
+
+        assert!(output.contains("authorization: Bearer my-token\n"));
+        assert!(output.contains("cookie: session=abc123\n"));
+    }
+}
+
+#[cfg(test)]
+mod test_format_response {
+    use super::*;
+
+    #[test]
+    fn it_should_format_response_redacting_set_cookie() {
+        let request_path: Uri = "http://localhost/login".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(header::SET_COOKIE, "session=abc123".parse().unwrap());
+        let body_bytes = Bytes::from("logged in");
+
+        let output = format_response(&request_path, StatusCode::OK, &headers, &body_bytes, false);
+
+        assert_eq!(
+            output,
+            "Response 200 OK for http://localhost/login\n\
+             set-cookie: [redacted]\n\
+             \n\
+             logged in"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use ::anyhow::anyhow;
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::flate2::read::DeflateDecoder;
+use ::flate2::read::GzDecoder;
+use ::flate2::read::ZlibDecoder;
+use ::hyper::body::Bytes;
+use ::hyper::header;
+use ::hyper::http::HeaderMap;
+use ::hyper::http::HeaderValue;
+use ::hyper::http::Method;
+use ::std::io::Read;
+
+/// Decompresses the body given, based on the `Content-Encoding` in the headers.
+///
+/// When there are multiple encodings, they are undone in reverse order.
+/// The body is returned as is when there is no `Content-Encoding`,
+/// when any of the encodings are not supported,
+/// or when there is no body to decompress (such as for `HEAD` requests).
+///
+/// Once decompressed, the `Content-Encoding` header is removed,
+/// and the `Content-Length` is set to the size of the decompressed body.
+/// This errors if the decompressed body is larger than the `max_size` given.
+pub(crate) fn decompress(
+    method: &Method,
+    headers: &mut HeaderMap,
+    body: Bytes,
+    max_size: usize,
+) -> Result<Bytes> {
+    if body.is_empty() || method == Method::HEAD {
+        return Ok(body);
+    }
+
+    let mut encodings = vec![];
+    for header in headers.get_all(header::CONTENT_ENCODING) {
+        let header_str = header
+            .to_str()
+            .context("Reading header 'Content-Encoding' as string")?;
+
+        for name in header_str.split(',').map(str::trim) {
+            if name.is_empty() {
+                continue;
+            }
+
+            match Encoding::from_name(name) {
+                Some(encoding) => encodings.push(encoding),
+                None => return Ok(body),
+            }
+        }
+    }
+
+    if encodings.is_empty() {
+        return Ok(body);
+    }
+
+    let mut body = body;
+    for encoding in encodings.iter().rev() {
+        body = encoding
+            .decompress(&body, max_size)
+            .with_context(|| format!("Decompressing body with encoding '{}'", encoding.name()))?;
+    }
+
+    headers.remove(header::CONTENT_ENCODING);
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+
+    Ok(body)
+}
+
+/// The content encodings which can be decompressed.
+#[derive(Debug, Clone, Copy)]
+enum Encoding {
+    Identity,
+    Gzip,
+    Deflate,
+    Brotli,
+}
+
+impl Encoding {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "identity" => Some(Self::Identity),
+            "gzip" | "x-gzip" => Some(Self::Gzip),
+            "deflate" => Some(Self::Deflate),
+            "br" => Some(Self::Brotli),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Identity => "identity",
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+            Self::Brotli => "br",
+        }
+    }
+
+    fn decompress(self, body: &[u8], max_size: usize) -> Result<Bytes> {
+        let decompressed = match self {
+            Self::Identity => body.to_vec(),
+            Self::Gzip => read_to_max_size(GzDecoder::new(body), max_size)?,
+            Self::Deflate => {
+                // Deflate is meant to be zlib wrapped, however some servers send it raw.
+                match read_to_max_size(ZlibDecoder::new(body), max_size) {
+                    Ok(decompressed) => decompressed,
+                    Err(_) => read_to_max_size(DeflateDecoder::new(body), max_size)?,
+                }
+            }
+            Self::Brotli => read_to_max_size(::brotli::Decompressor::new(body, 4096), max_size)?,
+        };
+
+        if decompressed.len() > max_size {
+            return Err(anyhow!(
+                "Decompressed response body is larger than the maximum size of {} bytes",
+                max_size
+            ));
+        }
+
+        Ok(Bytes::from(decompressed))
+    }
+}
+
+/// Reads up to one byte past the `max_size`,
+/// so a body which is too large is found without reading all of it.
+fn read_to_max_size<R>(reader: R, max_size: usize) -> Result<Vec<u8>>
+where
+    R: Read,
+{
+    let mut decompressed = vec![];
+    let limit = u64::try_from(max_size)
+        .unwrap_or(u64::MAX)
+        .saturating_add(1);
+    reader.take(limit).read_to_end(&mut decompressed)?;
+
+    Ok(decompressed)
+}
@@ -0,0 +1,57 @@
+use ::anyhow::Error;
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+use ::std::fmt::Result as FmtResult;
+use ::std::io::Error as IoError;
+use ::std::io::ErrorKind;
+use ::tokio::time::error::Elapsed;
+
+/// The way a `Request` expects to fail to reach the server.
+#[derive(Debug, Clone)]
+pub(crate) enum ExpectedFailure {
+    /// Any failure at all.
+    Any,
+
+    /// The server refused the connection.
+    ConnectionRefused,
+
+    /// The request took longer than its timeout.
+    Timeout,
+}
+
+impl ExpectedFailure {
+    pub(crate) fn is_match(&self, error: &Error) -> bool {
+        match self {
+            Self::Any => true,
+            Self::ConnectionRefused => is_io_error_kind(error, ErrorKind::ConnectionRefused),
+            Self::Timeout => {
+                let is_elapsed = error.chain().any(|cause| cause.is::<Elapsed>());
+                let is_hyper_timeout = error.chain().any(|cause| {
+                    cause
+                        .downcast_ref::<::hyper::Error>()
+                        .is_some_and(|hyper_error| hyper_error.is_timeout())
+                });
+
+                is_elapsed || is_hyper_timeout || is_io_error_kind(error, ErrorKind::TimedOut)
+            }
+        }
+    }
+}
+
+impl Display for ExpectedFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Any => write!(f, "fail"),
+            Self::ConnectionRefused => write!(f, "have its connection refused"),
+            Self::Timeout => write!(f, "time out"),
+        }
+    }
+}
+
+fn is_io_error_kind(error: &Error, kind: ErrorKind) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<IoError>()
+            .is_some_and(|io_error| io_error.kind() == kind)
+    })
+}
@@ -0,0 +1,44 @@
+use ::hyper::http::StatusCode;
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+use ::std::fmt::Result as FmtResult;
+
+/// The status code a `Request` expects to receive back.
+#[derive(Debug, Clone)]
+pub(crate) enum ExpectedStatus {
+    /// Any status code in the 2xx range.
+    Success,
+
+    /// Exactly this status code.
+    Exact(StatusCode),
+
+    /// Any one of these status codes.
+    OneOf(Vec<StatusCode>),
+}
+
+impl ExpectedStatus {
+    pub(crate) fn is_match(&self, status_code: StatusCode) -> bool {
+        match self {
+            Self::Success => status_code.is_success(),
+            Self::Exact(expected) => *expected == status_code,
+            Self::OneOf(expected) => expected.contains(&status_code),
+        }
+    }
+}
+
+impl Display for ExpectedStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Success => write!(f, "a 2xx status code"),
+            Self::Exact(expected) => write!(f, "status code {}", expected),
+            Self::OneOf(expected) => {
+                let expected_list = expected
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "one of status codes {}", expected_list)
+            }
+        }
+    }
+}
@@ -1,10 +1,12 @@
-use ::hyper::http::Method;
-use ::hyper::Uri;
+use ::hyper::http::HeaderName;
+use ::hyper::http::HeaderValue;
+use ::std::time::Duration;
 
+/// The defaults for a request, taken from the `Server` that created it.
 #[derive(Debug, Clone)]
 pub(crate) struct RequestConfig {
-    pub method: Method,
-    pub request_path: Uri,
     pub save_cookies: bool,
     pub content_type: Option<String>,
+    pub default_headers: Vec<(HeaderName, HeaderValue)>,
+    pub timeout: Option<Duration>,
 }
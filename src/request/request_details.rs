@@ -0,0 +1,9 @@
+use ::hyper::http::Method;
+use ::hyper::Uri;
+
+/// The specifics of a single request, such as where it is going.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestDetails {
+    pub method: Method,
+    pub request_path: Uri,
+}
@@ -0,0 +1,32 @@
+use ::hyper::http::request::Builder;
+use ::std::fmt::Debug;
+use ::std::fmt::Formatter;
+use ::std::fmt::Result as FmtResult;
+use ::std::sync::Arc;
+
+/// A function which changes the Hyper request builder, before the body is added.
+#[derive(Clone)]
+pub(crate) struct RequestMapper {
+    mapper: Arc<dyn Fn(Builder) -> Builder + Send + Sync>,
+}
+
+impl RequestMapper {
+    pub(crate) fn new<F>(mapper: F) -> Self
+    where
+        F: Fn(Builder) -> Builder + Send + Sync + 'static,
+    {
+        Self {
+            mapper: Arc::new(mapper),
+        }
+    }
+
+    pub(crate) fn apply(&self, request_builder: Builder) -> Builder {
+        (self.mapper)(request_builder)
+    }
+}
+
+impl Debug for RequestMapper {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "RequestMapper")
+    }
+}
@@ -0,0 +1,59 @@
+use ::anyhow::anyhow;
+use ::anyhow::Result;
+use ::hyper::body::Body;
+use ::hyper::body::Bytes;
+use ::hyper::body::HttpBody;
+use ::hyper::http::HeaderMap;
+use ::tokio::spawn;
+
+/// Wraps the body given, so the trailers are sent after it.
+///
+/// The new body is streamed, and so it has no known length.
+pub(crate) fn body_with_trailers(mut body: Body, trailers: HeaderMap) -> Body {
+    let (mut sender, trailers_body) = Body::channel();
+
+    spawn(async move {
+        while let Some(chunk_result) = body.data().await {
+            match chunk_result {
+                Ok(chunk) => {
+                    if sender.send_data(chunk).await.is_err() {
+                        return;
+                    }
+                }
+                Err(_) => {
+                    sender.abort();
+                    return;
+                }
+            }
+        }
+
+        let _ = sender.send_trailers(trailers).await;
+    });
+
+    trailers_body
+}
+
+/// Reads all of the body given, followed by any trailers sent after it.
+///
+/// An error is returned if the body is larger than the maximum size given.
+pub(crate) async fn read_body_with_trailers(
+    mut body: Body,
+    max_size: usize,
+) -> Result<(Bytes, Option<HeaderMap>)> {
+    let mut body_bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if body_bytes.len() + chunk.len() > max_size {
+            return Err(anyhow!(
+                "Response body is larger than the maximum size of {} bytes",
+                max_size
+            ));
+        }
+
+        body_bytes.extend_from_slice(&chunk);
+    }
+
+    let trailers = body.trailers().await?;
+
+    Ok((Bytes::from(body_bytes), trailers))
+}
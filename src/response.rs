@@ -1,20 +1,41 @@
 use ::anyhow::Context;
+use ::anyhow::Error;
+use ::anyhow::Result;
 use ::cookie::Cookie;
 use ::cookie::CookieJar;
 use ::hyper::body::Bytes;
 use ::hyper::http::header::AsHeaderName;
 use ::hyper::http::header::HeaderName;
+use ::hyper::http::header::CONTENT_LENGTH;
+use ::hyper::http::header::CONTENT_TYPE;
+use ::hyper::http::header::LOCATION;
 use ::hyper::http::header::SET_COOKIE;
 use ::hyper::http::response::Parts;
 use ::hyper::http::HeaderMap;
 use ::hyper::http::HeaderValue;
+use ::hyper::http::Response as HyperResponse;
 use ::hyper::http::StatusCode;
+use ::hyper::http::Version;
 use ::serde::Deserialize;
+use ::serde_json::to_string_pretty;
+use ::serde_json::Value;
 use ::std::convert::AsRef;
 use ::std::fmt::Debug;
 use ::std::fmt::Display;
+use ::std::ops::RangeInclusive;
+use ::std::sync::Arc;
+use ::std::time::Duration;
 use hyper::Uri;
 
+use crate::resolve_location;
+
+/// The maximum number of characters of the body to show in error messages.
+const BODY_SNIPPET_MAX_LENGTH: usize = 256;
+
+/// The maximum number of characters of the body to show when a status check fails.
+/// This is longer, as the body will often explain the error.
+const STATUS_BODY_SNIPPET_MAX_LENGTH: usize = 2048;
+
 ///
 /// The `Response` represents the result of a `Request`.
 /// It is returned when you call await on a `Request` object.
@@ -28,42 +49,222 @@ use hyper::Uri;
 pub struct Response {
     request_uri: Uri,
     headers: HeaderMap<HeaderValue>,
-    status_code: StatusCode,
+    status_code: Option<StatusCode>,
+    version: Version,
     response_body: Bytes,
+    trailers: Option<HeaderMap<HeaderValue>>,
+    transport_error: Option<Arc<Error>>,
+    duration: Duration,
 }
 
 impl Response {
-    pub(crate) fn new(request_uri: Uri, parts: Parts, response_body: Bytes) -> Self {
+    pub(crate) fn new(
+        request_uri: Uri,
+        parts: Parts,
+        response_body: Bytes,
+        trailers: Option<HeaderMap<HeaderValue>>,
+        duration: Duration,
+    ) -> Self {
         Self {
             request_uri,
             headers: parts.headers,
-            status_code: parts.status,
+            status_code: Some(parts.status),
+            version: parts.version,
             response_body,
+            trailers,
+            transport_error: None,
+            duration,
+        }
+    }
+
+    /// Creates a `Response` for a request that failed to reach the server.
+    /// There are no headers, no body, and no status code.
+    pub(crate) fn new_transport_failure(request_uri: Uri, transport_error: Error) -> Self {
+        Self {
+            request_uri,
+            headers: HeaderMap::new(),
+            status_code: None,
+            version: Version::default(),
+            response_body: Bytes::new(),
+            trailers: None,
+            transport_error: Some(Arc::new(transport_error)),
+            duration: Duration::ZERO,
         }
     }
 
     /// The URL that was used to produce this response.
     #[must_use]
-    pub fn request_uri<'a>(&'a self) -> &'a Uri {
+    pub fn request_uri(&self) -> &Uri {
         &self.request_uri
     }
 
+    /// The path that was requested to produce this response,
+    /// without the host or query. i.e. `/users/123`.
+    #[must_use]
+    pub fn request_path(&self) -> &str {
+        self.request_uri.path()
+    }
+
+    /// Returns the error for why the request failed to reach the server.
+    ///
+    /// This is only set when using `Request::expect_failure`
+    /// (or one of its variants), and the request failed. The error includes the full chain of causes,
+    /// such as the underlying Hyper and IO errors.
+    #[must_use]
+    pub fn transport_error(&self) -> Option<&Error> {
+        self.transport_error.as_deref()
+    }
+
+    /// Returns how long it took to receive this response.
+    ///
+    /// This is from when the request was sent,
+    /// until all of the body had been read.
+    /// When following redirects, this is for the last request only.
+    ///
+    /// For a request which failed to reach the server, this is zero.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
     /// Returns the raw underlying response, as it's raw bytes.
     #[must_use]
-    pub fn bytes<'a>(&'a self) -> &'a [u8] {
+    pub fn bytes(&self) -> &[u8] {
         &self.response_body
     }
 
-    /// Returns the underlying response, as a raw UTF-8 string.
+    /// Returns the raw underlying response, consuming the `Response`.
+    ///
+    /// This avoids copying the body.
+    #[must_use]
+    pub fn into_bytes(self) -> Bytes {
+        self.response_body
+    }
+
+    /// Returns the raw underlying response, as its raw bytes.
+    ///
+    /// This is the same as `Response::bytes`.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.response_body
+    }
+
+    /// Splits the `Response` into the path requested,
+    /// the parts of the response, and the body.
+    ///
+    /// This gives access to everything returned, for checks which
+    /// are not covered by the other methods. Such as the HTTP version.
+    /// The extensions in the parts are always empty.
+    ///
+    /// This will panic if the request failed to reach the server.
+    #[must_use]
+    pub fn into_parts(self) -> (String, Parts, Bytes) {
+        let mut hyper_response = HyperResponse::new(());
+        *hyper_response.status_mut() = self.status_code();
+        *hyper_response.version_mut() = self.version;
+        *hyper_response.headers_mut() = self.headers;
+        let (parts, _) = hyper_response.into_parts();
+
+        (
+            self.request_uri.path().to_string(),
+            parts,
+            self.response_body,
+        )
+    }
+
+    /// Returns true if the response has no body.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.response_body.is_empty()
+    }
+
+    /// Returns the underlying response, as a UTF-8 string.
+    ///
+    /// This will panic if the response is not valid UTF-8.
     #[must_use]
     pub fn text(&self) -> String {
-        String::from_utf8_lossy(&self.response_body).to_string()
+        String::from_utf8(self.response_body.to_vec())
+            .with_context(|| {
+                format!(
+                    "Reading response as UTF-8 text for request {}",
+                    self.request_uri
+                )
+            })
+            .unwrap()
     }
 
     /// The status_code of the response.
+    ///
+    /// This will panic if the request failed to reach the server,
+    /// as there is no status code. This can only happen when
+    /// using `Request::expect_failure`.
     #[must_use]
     pub fn status_code(&self) -> StatusCode {
         self.status_code
+            .with_context(|| {
+                format!(
+                    "No status code for response {}, the request failed to reach the server",
+                    self.request_uri
+                )
+            })
+            .unwrap()
+    }
+
+    /// Returns true if the status code is informational, in the 1xx range.
+    ///
+    /// This returns false if the request failed to reach the server.
+    #[must_use]
+    pub fn is_informational(&self) -> bool {
+        self.status_code
+            .is_some_and(|status_code| status_code.is_informational())
+    }
+
+    /// Returns true if the status code is a success, in the 2xx range.
+    ///
+    /// This returns false if the request failed to reach the server.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.status_code
+            .is_some_and(|status_code| status_code.is_success())
+    }
+
+    /// Returns true if the status code is a redirection, in the 3xx range.
+    ///
+    /// This returns false if the request failed to reach the server.
+    #[must_use]
+    pub fn is_redirection(&self) -> bool {
+        self.status_code
+            .is_some_and(|status_code| status_code.is_redirection())
+    }
+
+    /// Returns true if the status code is a client error, in the 4xx range.
+    ///
+    /// This returns false if the request failed to reach the server.
+    #[must_use]
+    pub fn is_client_error(&self) -> bool {
+        self.status_code
+            .is_some_and(|status_code| status_code.is_client_error())
+    }
+
+    /// Returns true if the status code is a server error, in the 5xx range.
+    ///
+    /// This returns false if the request failed to reach the server.
+    #[must_use]
+    pub fn is_server_error(&self) -> bool {
+        self.status_code
+            .is_some_and(|status_code| status_code.is_server_error())
+    }
+
+    /// Returns the `Content-Length` header of the response.
+    ///
+    /// `None` is returned if the header is missing,
+    /// or is not a valid length.
+    #[must_use]
+    pub fn content_length(&self) -> Option<u64> {
+        self.headers
+            .get(CONTENT_LENGTH)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.parse().ok())
     }
 
     /// Finds a header with the given name.
@@ -79,9 +280,17 @@ impl Response {
         self.headers.get(header_name).map(|h| h.to_owned())
     }
 
+    /// Returns the trailing headers sent after the body of the response.
+    ///
+    /// `None` is returned when no trailers were sent.
+    #[must_use]
+    pub fn trailers(&self) -> Option<&HeaderMap<HeaderValue>> {
+        self.trailers.as_ref()
+    }
+
     /// Returns the headers returned from the response.
     #[must_use]
-    pub fn headers<'a>(&'a self) -> &'a HeaderMap<HeaderValue> {
+    pub fn headers(&self) -> &HeaderMap<HeaderValue> {
         &self.headers
     }
 
@@ -108,22 +317,36 @@ impl Response {
             .unwrap()
     }
 
+    /// Finds all of the values for the header with the given name.
+    ///
+    /// An empty `Vec` is returned when no header was found.
+    #[must_use]
+    pub fn header_all<N>(&self, header_name: N) -> Vec<HeaderValue>
+    where
+        N: AsHeaderName,
+    {
+        self.iter_headers_by_name(header_name)
+            .map(|h| h.to_owned())
+            .collect()
+    }
+
     /// Iterates over all of the headers contained in the response.
-    pub fn iter_headers<'a>(&'a self) -> impl Iterator<Item = (&'a HeaderName, &'a HeaderValue)> {
+    pub fn iter_headers(&self) -> impl Iterator<Item = (&HeaderName, &HeaderValue)> {
         self.headers.iter()
     }
 
     /// Iterates over all of the headers for a specific name, contained in the response.
-    pub fn iter_headers_by_name<'a, N>(
-        &'a self,
-        header_name: N,
-    ) -> impl Iterator<Item = &'a HeaderValue>
+    pub fn iter_headers_by_name<N>(&self, header_name: N) -> impl Iterator<Item = &HeaderValue>
     where
         N: AsHeaderName,
     {
         self.headers.get_all(header_name).iter()
     }
 
+    /// Finds a cookie returned by the response with the given name.
+    /// Including all of its attributes, such as `HttpOnly` and `Max-Age`.
+    ///
+    /// `None` is returned when no cookie was found.
     #[must_use]
     pub fn maybe_cookie(&self, cookie_name: &str) -> Option<Cookie<'static>> {
         for cookie in self.iter_cookies() {
@@ -135,6 +358,10 @@ impl Response {
         None
     }
 
+    /// Finds a cookie returned by the response with the given name.
+    /// Including all of its attributes, such as `HttpOnly` and `Max-Age`.
+    ///
+    /// If no cookie is found, then this will panic.
     #[must_use]
     pub fn cookie(&self, cookie_name: &str) -> Cookie<'static> {
         self.maybe_cookie(cookie_name)
@@ -163,7 +390,6 @@ impl Response {
     }
 
     /// Iterate over all of the cookies in the response.
-    #[must_use]
     pub fn iter_cookies<'a>(&'a self) -> impl Iterator<Item = Cookie<'a>> {
         self.iter_headers_by_name(SET_COOKIE).map(|header| {
             let header_str = header
@@ -189,19 +415,104 @@ impl Response {
 
     /// Reads the response from the server as JSON text,
     /// and then deserialise the contents into the structure given.
+    ///
+    /// This will panic if the contents cannot be deserialised.
     #[must_use]
     pub fn json<T>(&self) -> T
     where
         for<'de> T: Deserialize<'de>,
     {
-        serde_json::from_slice::<T>(&self.response_body)
-            .with_context(|| {
-                format!(
-                    "Deserializing response from JSON for request {}",
-                    self.request_uri
-                )
-            })
-            .unwrap()
+        self.maybe_json().unwrap()
+    }
+
+    /// Reads the response from the server as JSON text,
+    /// and then deserialise the contents into the structure given.
+    /// This consumes the `Response`.
+    ///
+    /// This will panic if the contents cannot be deserialised.
+    #[must_use]
+    pub fn into_json<T>(self) -> T
+    where
+        for<'de> T: Deserialize<'de>,
+    {
+        self.maybe_json().unwrap()
+    }
+
+    /// Asserts the response has a client or server error status code,
+    /// and then deserialises the error returned as JSON,
+    /// into the structure given.
+    ///
+    /// This will panic if the status code is not a 4xx or 5xx,
+    /// or if the contents cannot be deserialised.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Json;
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::get;
+    /// # use ::kantan::http::StatusCode;
+    /// # use ::kantan::Server;
+    /// # use ::serde::Deserialize;
+    /// # use ::serde_json::json;
+    /// #
+    /// # #[derive(Deserialize)]
+    /// # struct ApiError {
+    /// #     error: String,
+    /// # }
+    /// #
+    /// # let app = Router::new().route("/missing", get(|| async {
+    /// #     (StatusCode::NOT_FOUND, Json(json!({ "error": "not found" })))
+    /// # }));
+    /// # let server = Server::new_with_router(app).unwrap();
+    /// let error: ApiError = server.get("/missing").await.expect_error_json();
+    /// # })
+    /// ```
+    #[must_use]
+    pub fn expect_error_json<E>(self) -> E
+    where
+        for<'de> E: Deserialize<'de>,
+    {
+        let status_code = self.status_code();
+        assert!(
+            status_code.is_client_error() || status_code.is_server_error(),
+            "Expected an error status code for request {}, received {}, with body '{}'",
+            self.request_uri,
+            status_code,
+            self.status_body_snippet()
+        );
+
+        self.into_json()
+    }
+
+    /// Reads the response from the server as JSON text,
+    /// and then deserialise the contents into the structure given.
+    ///
+    /// An error is returned if the contents cannot be deserialised.
+    pub fn maybe_json<T>(&self) -> Result<T>
+    where
+        for<'de> T: Deserialize<'de>,
+    {
+        serde_json::from_slice::<T>(&self.response_body).with_context(|| {
+            format!(
+                "Deserializing response from JSON for request {}, received body '{}'",
+                self.request_uri,
+                self.text_snippet()
+            )
+        })
+    }
+
+    /// Asserts the response has no body,
+    /// such as for a `204 No Content` or a `HEAD` request.
+    pub fn assert_empty(self) -> Self {
+        assert!(
+            self.is_empty(),
+            "Expected body of request {} to be empty, received {} bytes '{}'",
+            self.request_uri,
+            self.response_body.len(),
+            self.text_snippet()
+        );
+
+        self
     }
 
     /// This performs an assertion comparing the whole body of the response,
@@ -211,7 +522,187 @@ impl Response {
         C: AsRef<str>,
     {
         let other_contents = other.as_ref();
-        assert_eq!(&self.text(), other_contents);
+        let text = self.text();
+        assert!(
+            text == other_contents,
+            "Expected body of request {} to be '{}', received '{}'",
+            self.request_uri,
+            truncate_text(other_contents),
+            truncate_text(&text)
+        );
+
+        self
+    }
+
+    /// This performs an assertion that the body of the response,
+    /// contains the text provided.
+    pub fn assert_text_contains<C>(self, other: C) -> Self
+    where
+        C: AsRef<str>,
+    {
+        let other_contents = other.as_ref();
+        let text = self.text();
+        assert!(
+            text.contains(other_contents),
+            "Expected body of request {} to contain '{}', received '{}'",
+            self.request_uri,
+            truncate_text(other_contents),
+            truncate_text(&text)
+        );
+
+        self
+    }
+
+    /// Asserts the header with the given name matches the value given.
+    /// If there are multiple headers with the same name,
+    /// then only the first is compared.
+    ///
+    /// This will panic if the header is missing, or if it does not match.
+    pub fn assert_header<N>(self, header_name: N, expected: &str) -> Self
+    where
+        N: AsHeaderName + Display + Clone,
+    {
+        let debug_header = header_name.clone();
+        let header = self.header(header_name);
+        let header_str = header
+            .to_str()
+            .with_context(|| {
+                format!(
+                    "Reading header {} as string for response {}",
+                    debug_header, self.request_uri
+                )
+            })
+            .unwrap();
+
+        assert_eq!(
+            header_str, expected,
+            "Expected header {} to be '{}' for request {}, received '{}'",
+            debug_header, expected, self.request_uri, header_str
+        );
+
+        self
+    }
+
+    /// Asserts there is no header with the given name.
+    ///
+    /// This is useful for checking headers have been stripped,
+    /// such as `Server` or `X-Powered-By`.
+    /// This will panic if the header is present, showing its value.
+    pub fn assert_header_missing<N>(self, header_name: N) -> Self
+    where
+        N: AsHeaderName + Display + Clone,
+    {
+        let debug_header = header_name.clone();
+        if let Some(header) = self.maybe_header(header_name) {
+            panic!(
+                "Expected header {} to be missing for request {}, received {:?}",
+                debug_header, self.request_uri, header
+            );
+        }
+
+        self
+    }
+
+    /// Asserts the response is a redirect, to the location given.
+    ///
+    /// The location can be relative or absolute, and it will match
+    /// if both point to the same place. i.e. `/login` will match
+    /// `http://localhost:3000/login` when the request was to `localhost:3000`.
+    pub fn assert_location(self, expected: &str) -> Self {
+        let status_code = self.status_code();
+        assert!(
+            status_code.is_redirection(),
+            "Expected a redirect status code for request {}, received {}, with body '{}'",
+            self.request_uri,
+            status_code,
+            self.status_body_snippet()
+        );
+
+        let location = self
+            .maybe_header(LOCATION)
+            .map(|header| String::from_utf8_lossy(header.as_bytes()).to_string())
+            .with_context(|| {
+                format!(
+                    "Expected location '{}' for request {}, but no location was returned",
+                    expected, self.request_uri
+                )
+            })
+            .unwrap();
+
+        let is_matching = location == expected || {
+            let maybe_resolved_location = resolve_location(&self.request_uri, &location).ok();
+            let maybe_resolved_expected = resolve_location(&self.request_uri, expected).ok();
+
+            maybe_resolved_location.is_some() && maybe_resolved_location == maybe_resolved_expected
+        };
+        assert!(
+            is_matching,
+            "Expected location '{}' for request {}, received '{}'",
+            expected, self.request_uri, location
+        );
+
+        self
+    }
+
+    /// Asserts the `Content-Type` of the response matches the mime type given.
+    ///
+    /// Any parameters on the content type, such as `; charset=utf-8`,
+    /// are ignored. The mime types are compared case insensitively.
+    pub fn assert_content_type(self, expected: &str) -> Self {
+        let content_type = self
+            .maybe_header(CONTENT_TYPE)
+            .map(|header| String::from_utf8_lossy(header.as_bytes()).to_string())
+            .with_context(|| {
+                format!(
+                    "Expected content type '{}' for request {}, but no content type was returned",
+                    expected, self.request_uri
+                )
+            })
+            .unwrap();
+
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        assert!(
+            mime.eq_ignore_ascii_case(expected.trim()),
+            "Expected content type '{}' for request {}, received '{}'",
+            expected,
+            self.request_uri,
+            content_type
+        );
+
+        self
+    }
+
+    /// Asserts the `Content-Type` of the response is `application/json`.
+    pub fn assert_content_type_json(self) -> Self {
+        self.assert_content_type("application/json")
+    }
+
+    /// Asserts the response sets a cookie with the given name.
+    pub fn assert_cookie(self, cookie_name: &str) -> Self {
+        assert!(
+            self.maybe_cookie(cookie_name).is_some(),
+            "Expected cookie {} to be set for request {}, but it was not found",
+            cookie_name,
+            self.request_uri
+        );
+
+        self
+    }
+
+    /// Asserts the response sets a cookie with the given name,
+    /// and that it has the value given.
+    pub fn assert_cookie_value(self, cookie_name: &str, expected: &str) -> Self {
+        let cookie = self.cookie(cookie_name);
+
+        assert_eq!(
+            cookie.value(),
+            expected,
+            "Expected cookie {} to be '{}' for request {}, received '{}'",
+            cookie_name,
+            expected,
+            self.request_uri,
+            cookie.value()
+        );
 
         self
     }
@@ -229,36 +720,332 @@ impl Response {
         for<'de> T: Deserialize<'de> + PartialEq<T> + Debug,
     {
         let own_json: T = self.json();
-        assert_eq!(own_json, *other);
+        assert!(
+            own_json == *other,
+            "Expected JSON of request {} to be:\n{:#?}\nreceived:\n{:#?}",
+            self.request_uri,
+            other,
+            own_json
+        );
+
+        self
+    }
+
+    /// Deserializes the contents of the request as a JSON `Value`,
+    /// and asserts if it is structurally the same as the value given.
+    ///
+    /// If `expected` does not match, then this will panic,
+    /// printing both as pretty JSON.
+    pub fn assert_json_eq(self, expected: Value) -> Self {
+        let own_json: Value = self.json();
+        assert!(
+            own_json == expected,
+            "Expected JSON of request {} to be:\n{}\nreceived:\n{}",
+            self.request_uri,
+            to_string_pretty(&expected).unwrap_or_default(),
+            to_string_pretty(&own_json).unwrap_or_default()
+        );
+
+        self
+    }
+
+    /// Deserializes the contents of the request as a JSON `Value`,
+    /// and asserts it contains everything in the partial value given.
+    ///
+    /// Objects may have extra keys which are not in `partial`,
+    /// such as ids and timestamps. Arrays must be the same length,
+    /// with each item matched in order. All other values must be equal.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Json;
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::get;
+    /// # use ::kantan::Server;
+    /// use ::serde_json::json;
+    /// #
+    /// # let app = Router::new().route("/users/1", get(|| async {
+    /// #     Json(json!({
+    /// #         "id": 1,
+    /// #         "name": "Joe",
+    /// #         "address": { "street": "Baker Street", "city": "London" },
+    /// #     }))
+    /// # }));
+    /// # let server = Server::new_with_router(app).unwrap();
+    ///
+    /// server.get("/users/1").await.assert_json_contains(json!({
+    ///     "name": "Joe",
+    ///     "address": { "city": "London" },
+    /// }));
+    /// # })
+    /// ```
+    pub fn assert_json_contains(self, partial: Value) -> Self {
+        let own_json: Value = self.json();
+        assert!(
+            is_json_contained(&partial, &own_json),
+            "Expected JSON of request {} to contain:\n{}\nreceived:\n{}",
+            self.request_uri,
+            to_string_pretty(&partial).unwrap_or_default(),
+            to_string_pretty(&own_json).unwrap_or_default()
+        );
 
         self
     }
 
+    /// Asserts the response status code is 400 Bad Request.
     pub fn assert_status_bad_request(self) -> Self {
         self.assert_status(StatusCode::BAD_REQUEST)
     }
 
+    /// Asserts the response status code is 404 Not Found.
     pub fn assert_status_not_found(self) -> Self {
         self.assert_status(StatusCode::NOT_FOUND)
     }
 
+    /// Asserts the response status code is 200 OK.
     pub fn assert_status_ok(self) -> Self {
         self.assert_status(StatusCode::OK)
     }
 
+    /// Asserts the response status code is anything but 200 OK.
     pub fn assert_status_not_ok(self) -> Self {
         self.assert_not_status(StatusCode::OK)
     }
 
+    /// Asserts the response status code is within the 2xx range.
+    pub fn assert_status_success(self) -> Self {
+        let status_code = self.status_code();
+        assert!(
+            status_code.is_success(),
+            "Expected a 2xx status code for request {}, received {}, with body '{}'",
+            self.request_uri,
+            status_code,
+            self.status_body_snippet()
+        );
+
+        self
+    }
+
+    /// Asserts the response status code is within the inclusive range given.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::get;
+    /// # use ::kantan::Server;
+    /// #
+    /// # let app = Router::new().route("/users", get(|| async {}));
+    /// # let server = Server::new_with_router(app).unwrap();
+    /// server.get("/users").await.assert_status_in_range(200..=299);
+    /// # })
+    /// ```
+    pub fn assert_status_in_range(self, range: RangeInclusive<u16>) -> Self {
+        let status_code = self.status_code();
+        assert!(
+            range.contains(&status_code.as_u16()),
+            "Expected status code within {}..={} for request {}, received {}, with body '{}'",
+            range.start(),
+            range.end(),
+            self.request_uri,
+            status_code,
+            self.status_body_snippet()
+        );
+
+        self
+    }
+
+    /// Asserts the response was received within the time given.
+    ///
+    /// See `Response::duration` for what is included in the time taken.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::get;
+    /// # use ::kantan::Server;
+    /// use ::std::time::Duration;
+    /// #
+    /// # let app = Router::new().route("/users", get(|| async {}));
+    /// # let server = Server::new_with_router(app).unwrap();
+    ///
+    /// server.get("/users").await.assert_faster_than(Duration::from_millis(100));
+    /// # })
+    /// ```
+    pub fn assert_faster_than(self, budget: Duration) -> Self {
+        assert!(
+            self.duration < budget,
+            "Expected request {} to be faster than {:?}, took {:?}",
+            self.request_uri,
+            budget,
+            self.duration
+        );
+
+        self
+    }
+
+    /// Asserts the response status code matches the one given.
+    ///
+    /// When it does not match, the start of the body is shown in the panic.
     pub fn assert_status(self, status_code: StatusCode) -> Self {
-        assert_eq!(self.status_code(), status_code);
+        assert_eq!(
+            self.status_code(),
+            status_code,
+            "Expected status code {} for request {}, received {}, with body '{}'",
+            status_code,
+            self.request_uri,
+            self.status_code(),
+            self.status_body_snippet()
+        );
 
         self
     }
 
+    /// Asserts the response status code is anything but the one given.
     pub fn assert_not_status(self, status_code: StatusCode) -> Self {
-        assert_ne!(self.status_code(), status_code);
+        assert_ne!(
+            self.status_code(),
+            status_code,
+            "Expected status code to not be {} for request {}, with body '{}'",
+            status_code,
+            self.request_uri,
+            self.status_body_snippet()
+        );
 
         self
     }
+
+    /// Returns the start of the body as text, for use in error messages.
+    pub(crate) fn text_snippet(&self) -> String {
+        truncate_text(&String::from_utf8_lossy(&self.response_body))
+    }
+
+    /// Returns the start of the body as text, for when a status check fails.
+    pub(crate) fn status_body_snippet(&self) -> String {
+        truncate_text_to(
+            &String::from_utf8_lossy(&self.response_body),
+            STATUS_BODY_SNIPPET_MAX_LENGTH,
+        )
+    }
+}
+
+/// Shortens the text given, for use in error messages.
+pub(crate) fn truncate_text(text: &str) -> String {
+    truncate_text_to(text, BODY_SNIPPET_MAX_LENGTH)
+}
+
+fn truncate_text_to(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    let snippet: String = text.chars().take(max_length).collect();
+    format!("{}...", snippet)
+}
+
+/// Returns true if everything in the partial value is within the full value.
+fn is_json_contained(partial: &Value, full: &Value) -> bool {
+    match (partial, full) {
+        (Value::Object(partial_map), Value::Object(full_map)) => {
+            partial_map.iter().all(|(key, partial_value)| {
+                full_map
+                    .get(key)
+                    .is_some_and(|full_value| is_json_contained(partial_value, full_value))
+            })
+        }
+        (Value::Array(partial_items), Value::Array(full_items)) => {
+            partial_items.len() == full_items.len()
+                && partial_items
+                    .iter()
+                    .zip(full_items)
+                    .all(|(partial_item, full_item)| is_json_contained(partial_item, full_item))
+        }
+        _ => partial == full,
+    }
+}
+
+#[cfg(test)]
+mod test_is_json_contained {
+    use super::*;
+
+    use ::serde_json::json;
+
+    #[test]
+    fn it_should_match_equal_values() {
+        assert!(is_json_contained(&json!(1), &json!(1)));
+        assert!(is_json_contained(&json!("a"), &json!("a")));
+        assert!(!is_json_contained(&json!(1), &json!(2)));
+    }
+
+    #[test]
+    fn it_should_ignore_extra_keys() {
+        let full = json!({ "id": 123, "name": "Joe" });
+
+        assert!(is_json_contained(&json!({ "name": "Joe" }), &full));
+        assert!(is_json_contained(&json!({}), &full));
+    }
+
+    #[test]
+    fn it_should_not_match_missing_keys() {
+        let full = json!({ "name": "Joe" });
+
+        assert!(!is_json_contained(&json!({ "age": 30 }), &full));
+    }
+
+    #[test]
+    fn it_should_match_nested_objects() {
+        let full = json!({ "address": { "city": "London", "postcode": "E1" } });
+
+        assert!(is_json_contained(
+            &json!({ "address": { "city": "London" } }),
+            &full
+        ));
+        assert!(!is_json_contained(
+            &json!({ "address": { "city": "Paris" } }),
+            &full
+        ));
+    }
+
+    #[test]
+    fn it_should_match_arrays_in_order() {
+        let full = json!([{ "id": 1, "name": "a" }, { "id": 2, "name": "b" }]);
+
+        assert!(is_json_contained(&json!([{ "id": 1 }, { "id": 2 }]), &full));
+        assert!(!is_json_contained(
+            &json!([{ "id": 2 }, { "id": 1 }]),
+            &full
+        ));
+    }
+
+    #[test]
+    fn it_should_not_match_arrays_of_different_lengths() {
+        assert!(!is_json_contained(&json!([1]), &json!([1, 2])));
+    }
+}
+
+#[cfg(test)]
+mod test_truncate_text_to {
+    use super::*;
+
+    #[test]
+    fn it_should_keep_short_text() {
+        assert_eq!(truncate_text_to("hello", 5), "hello");
+    }
+
+    #[test]
+    fn it_should_shorten_long_text() {
+        assert_eq!(truncate_text_to("hello world", 5), "hello...");
+    }
+
+    #[test]
+    fn it_should_count_characters_not_bytes() {
+        assert_eq!(truncate_text_to("héllo wörld", 5), "héllo...");
+    }
+
+    #[test]
+    fn it_should_show_up_to_2kb_of_body_for_status_checks() {
+        let text = "a".repeat(STATUS_BODY_SNIPPET_MAX_LENGTH + 1);
+        let snippet = truncate_text_to(&text, STATUS_BODY_SNIPPET_MAX_LENGTH);
+
+        assert_eq!(snippet.len(), 2048 + "...".len());
+    }
 }
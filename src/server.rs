@@ -1,16 +1,37 @@
 use ::anyhow::Context;
 use ::anyhow::Result;
+use ::axum::Router;
 use ::cookie::Cookie;
 use ::cookie::CookieJar;
+use ::futures_util::future::join_all;
+use ::hyper::client::Builder;
+use ::hyper::client::HttpConnector;
 use ::hyper::http::Method;
+use ::hyper::http::Uri;
+use ::hyper_tls::HttpsConnector;
+use ::std::future::IntoFuture;
+#[cfg(unix)]
+use ::std::path::Path;
 use ::std::sync::Arc;
 use ::std::sync::Mutex;
+use ::std::time::Duration;
 
 use crate::Request;
+use crate::Response;
+use crate::ServerConfig;
+
+mod http_client;
+pub(crate) use self::http_client::*;
 
 mod inner_server;
 pub(crate) use self::inner_server::*;
 
+mod request_path;
+pub use self::request_path::*;
+
+mod server_task;
+pub(crate) use self::server_task::*;
+
 ///
 /// The `Server` represents your application, running as a web server,
 /// and you can make web requests to your application.
@@ -19,31 +40,389 @@ pub(crate) use self::inner_server::*;
 /// This allows you Allowing you to create new requests that will go to this server.
 ///
 /// You can make a request against the `Server` by calling the
-/// `get`, `post`, `put`, `delete`, and `patch` methods (you can also use `method`).
+/// `get`, `post`, `put`, `delete`, `patch`, `head`, and `options` methods
+/// (you can also use `method`).
+///
+/// The path given can be a `&str`, a `String`, or a `Uri` (see `RequestPath`).
+/// Any query in the path is kept.
+/// Characters not allowed in a path, such as spaces, are percent-encoded.
+/// Full urls, with a scheme such as `http://`, are rejected.
+///
+/// Cloning a `Server` is cheap, and the clones all share the cookies saved.
+/// So a session started through one clone is also used for requests made through the others.
+/// Everything else is copied, so changing a clone (such as with `Server::with_base_path`)
+/// leaves the original unchanged.
 ///
 #[derive(Debug)]
 pub struct Server {
     inner: Arc<Mutex<InnerServer>>,
 }
 
+impl Clone for Server {
+    fn clone(&self) -> Self {
+        let inner_test_server = InnerServer::with_this(&self.inner, "clone", |this| this.clone())
+            .context("Trying to clone Server")
+            .unwrap();
+        let inner_mutex = Mutex::new(inner_test_server);
+        let inner = Arc::new(inner_mutex);
+
+        Self { inner }
+    }
+}
+
 impl Server {
-    /// This will take the given app, and run it.
-    /// It will use a randomly selected port for running.
+    /// Creates a `Server` for making requests to an already running server,
+    /// at the address given (i.e. `http://localhost:3000`).
+    ///
+    /// If the address has no scheme, then `http` is used.
     ///
     /// This is the same as creating a new `Server` with a configuration,
     /// and passing `ServerConfig::default()`.
     pub fn new(server_address: String) -> Result<Self> {
-        let inner_test_server = InnerServer::new(server_address)?;
+        Self::new_with_config(server_address, ServerConfig::default())
+    }
+
+    /// This is very similar to `Server::new()`,
+    /// however you can customise some of the configuration.
+    ///
+    /// See the `ServerConfig` for more information on each configuration setting.
+    pub fn new_with_config(server_address: String, config: ServerConfig) -> Result<Self> {
+        let inner_test_server = InnerServer::new(server_address, config)?;
+        let inner_mutex = Mutex::new(inner_test_server);
+        let inner = Arc::new(inner_mutex);
+
+        Ok(Self { inner })
+    }
+
+    /// Creates a `Server` for making requests to an already running server,
+    /// listening on the Unix domain socket given.
+    ///
+    /// This is the same as calling `Server::new_unix_with_config`,
+    /// and passing `ServerConfig::default()`.
+    #[cfg(unix)]
+    pub fn new_unix<P>(socket_path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new_unix_with_config(socket_path, ServerConfig::default())
+    }
+
+    /// This is very similar to `Server::new_unix()`,
+    /// however you can customise some of the configuration.
+    ///
+    /// See the `ServerConfig` for more information on each configuration setting.
+    #[cfg(unix)]
+    pub fn new_unix_with_config<P>(socket_path: P, config: ServerConfig) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let inner_test_server = InnerServer::new_unix(socket_path.as_ref(), config)?;
         let inner_mutex = Mutex::new(inner_test_server);
         let inner = Arc::new(inner_mutex);
 
         Ok(Self { inner })
     }
 
+    /// This will take the given app, and run it in the background.
+    /// It will use a randomly selected port on localhost for running.
+    ///
+    /// The app is stopped when the `Server`, and all of its clones, are dropped.
+    /// Requests already in progress are given up to a second to finish first.
+    /// This must be called from within a Tokio runtime, and returns an error otherwise.
+    ///
+    /// This is the same as calling `Server::new_with_router_and_config`,
+    /// and passing `ServerConfig::default()`.
+    pub fn new_with_router(app: Router) -> Result<Self> {
+        Self::new_with_router_and_config(app, ServerConfig::default())
+    }
+
+    /// This is very similar to `Server::new_with_router()`,
+    /// however you can customise some of the configuration.
+    ///
+    /// See the `ServerConfig` for more information on each configuration setting.
+    pub fn new_with_router_and_config(app: Router, config: ServerConfig) -> Result<Self> {
+        let inner_test_server = InnerServer::new_with_router(app, 0, config)?;
+        let inner_mutex = Mutex::new(inner_test_server);
+        let inner = Arc::new(inner_mutex);
+
+        Ok(Self { inner })
+    }
+
+    /// This will take the given app, and run it in the background,
+    /// on the port given on localhost. i.e. `http://127.0.0.1:3000`.
+    ///
+    /// This is useful when something outside of the test needs a known port.
+    /// An error is returned if the port is already in use.
+    ///
+    /// This is the same as calling `Server::new_with_port_and_config`,
+    /// and passing `ServerConfig::default()`.
+    pub fn new_with_port(app: Router, port: u16) -> Result<Self> {
+        Self::new_with_port_and_config(app, port, ServerConfig::default())
+    }
+
+    /// This is very similar to `Server::new_with_port()`,
+    /// however you can customise some of the configuration.
+    ///
+    /// See the `ServerConfig` for more information on each configuration setting.
+    pub fn new_with_port_and_config(app: Router, port: u16, config: ServerConfig) -> Result<Self> {
+        let inner_test_server = InnerServer::new_with_router(app, port, config)?;
+        let inner_mutex = Mutex::new(inner_test_server);
+        let inner = Arc::new(inner_mutex);
+
+        Ok(Self { inner })
+    }
+
+    /// Returns the address of the server requests are sent to.
+    ///
+    /// When running an app with `Server::new_with_router`,
+    /// this will contain the port it was bound to.
+    /// i.e. `http://127.0.0.1:54321`.
+    pub fn server_address(&self) -> Uri {
+        InnerServer::with_this(&self.inner, "server_address", |this| {
+            this.server_address().clone()
+        })
+        .context("Trying to get server_address")
+        .unwrap()
+    }
+
+    /// Returns the port of the server requests are sent to.
+    ///
+    /// When the address has no port, the default for its scheme is returned.
+    /// This will panic if there is no port, such as for a Unix domain socket.
+    #[must_use]
+    pub fn port(&self) -> u16 {
+        InnerServer::with_this(&self.inner, "port", |this| this.port())
+            .and_then(|port| port)
+            .context("Trying to get port")
+            .unwrap()
+    }
+
+    /// Returns the address of the server requests are sent to,
+    /// as a string without a trailing slash. i.e. `http://127.0.0.1:54321`.
+    ///
+    /// This is useful for building urls for other clients,
+    /// such as for opening a WebSocket.
+    #[must_use]
+    pub fn base_url(&self) -> String {
+        InnerServer::with_this(&self.inner, "base_url", |this| this.base_url())
+            .and_then(|base_url| base_url)
+            .context("Trying to get base_url")
+            .unwrap()
+    }
+
+    /// Sets a path to prefix onto the path of all requests.
+    ///
+    /// For example with a base path of `/api`,
+    /// calling `server.get("/users")` will request `/api/users`.
+    pub fn with_base_path(mut self, base_path: &str) -> Self {
+        InnerServer::set_base_path(&mut self.inner, base_path)
+            .context("Trying to set base_path")
+            .unwrap();
+
+        self
+    }
+
+    /// Sets the content type to use for all requests created after this.
+    ///
+    /// This can be overridden on each request, by calling `Request::content_type`.
+    /// Any whitespace around the content type is removed.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::kantan::Server;
+    /// #
+    /// # let app = Router::new();
+    /// let server = Server::new_with_router(app)
+    ///     .unwrap()
+    ///     .with_default_content_type("application/json");
+    /// # })
+    /// ```
+    pub fn with_default_content_type(mut self, content_type: &str) -> Self {
+        InnerServer::set_default_content_type(&mut self.inner, content_type)
+            .context("Trying to set default_content_type")
+            .unwrap();
+
+        self
+    }
+
+    /// Sets the longest time requests created after this can take.
+    /// Any request taking longer will fail.
+    ///
+    /// This guards a whole test suite against requests which hang.
+    /// It can be overridden on each request, by calling `Request::timeout`.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::kantan::Server;
+    /// use ::std::time::Duration;
+    /// #
+    /// # let app = Router::new();
+    ///
+    /// let server = Server::new_with_router(app)
+    ///     .unwrap()
+    ///     .with_default_timeout(Duration::from_secs(5));
+    /// # })
+    /// ```
+    pub fn with_default_timeout(mut self, default_timeout: Duration) -> Self {
+        InnerServer::set_default_timeout(&mut self.inner, default_timeout)
+            .context("Trying to set default_timeout")
+            .unwrap();
+
+        self
+    }
+
+    /// Sets the Hyper client used for sending requests over TCP,
+    /// built from the `Builder` and connector given.
+    ///
+    /// This allows the connector to be customised,
+    /// such as to trust the self-signed certificate of a test server.
+    ///
+    /// ```rust
+    /// use ::hyper::client::HttpConnector;
+    /// use ::hyper::Client;
+    /// use ::hyper_tls::HttpsConnector;
+    /// use ::kantan::Server;
+    /// use ::native_tls::TlsConnector;
+    ///
+    /// let tls = TlsConnector::builder()
+    ///     .danger_accept_invalid_certs(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let mut http = HttpConnector::new();
+    /// http.enforce_http(false);
+    /// let https = HttpsConnector::from((http, tls.into()));
+    ///
+    /// let server = Server::new("https://localhost:3000".to_string())
+    ///     .unwrap()
+    ///     .with_client(Client::builder(), https);
+    /// ```
+    ///
+    /// `Request::http2_prior_knowledge` and `Request::no_host_header`
+    /// still use this builder and connector, with just that option changed.
+    ///
+    /// This will panic if the `Server` was created with `Server::new_unix`,
+    /// as its requests are not sent over TCP.
+    pub fn with_client(
+        mut self,
+        builder: Builder,
+        connector: HttpsConnector<HttpConnector>,
+    ) -> Self {
+        InnerServer::set_client(
+            &mut self.inner,
+            HttpClient::new_tcp_with(builder, connector),
+        )
+        .context("Trying to set client")
+        .unwrap();
+
+        self
+    }
+
+    /// Requests made after this will save any cookies returned,
+    /// for use on future requests.
+    ///
+    /// This can be overridden on each request,
+    /// by calling `Request::do_not_save_cookies`.
+    pub fn save_cookies(&mut self) {
+        InnerServer::set_save_cookies(&mut self.inner, true)
+            .context("Trying to save_cookies")
+            .unwrap()
+    }
+
+    /// Requests made after this will _not_ save cookies returned.
+    ///
+    /// This is the default behaviour.
+    /// It can be overridden on each request, by calling `Request::do_save_cookies`.
+    pub fn do_not_save_cookies(&mut self) {
+        InnerServer::set_save_cookies(&mut self.inner, false)
+            .context("Trying to do_not_save_cookies")
+            .unwrap()
+    }
+
+    /// Finds a cookie stored on the server with the given name.
+    /// These are the cookies which will be sent on future requests.
+    ///
+    /// `None` is returned when no cookie was found.
+    #[must_use]
+    pub fn maybe_cookie(&self, cookie_name: &str) -> Option<Cookie<'static>> {
+        InnerServer::with_this(&self.inner, "maybe_cookie", |this| this.cookies())
+            .and_then(|cookies| cookies)
+            .map(|cookies| cookies.get(cookie_name).cloned())
+            .with_context(|| format!("Trying to get cookie '{}'", cookie_name))
+            .unwrap()
+    }
+
+    /// Finds a cookie stored on the server with the given name.
+    /// These are the cookies which will be sent on future requests.
+    ///
+    /// If no cookie is found, then this will panic.
+    #[must_use]
+    pub fn cookie(&self, cookie_name: &str) -> Cookie<'static> {
+        self.maybe_cookie(cookie_name)
+            .with_context(|| format!("Cannot find cookie '{}' stored on the server", cookie_name))
+            .unwrap()
+    }
+
+    /// Returns all of the cookies stored on the server,
+    /// which will be sent on future requests.
+    #[must_use]
+    pub fn cookies(&self) -> CookieJar {
+        InnerServer::with_this(&self.inner, "cookies", |this| this.cookies())
+            .and_then(|cookies| cookies)
+            .context("Trying to get cookies")
+            .unwrap()
+    }
+
+    /// Returns a copy of all the cookies currently stored,
+    /// which can be put back later using `Server::restore_cookies`.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::post;
+    /// # use ::kantan::Server;
+    /// #
+    /// # let app = Router::new().route("/logout", post(|| async {}));
+    /// # let mut server = Server::new_with_router(app).unwrap();
+    /// let logged_in_cookies = server.snapshot_cookies();
+    /// server.post("/logout").await;
+    ///
+    /// server.restore_cookies(logged_in_cookies);
+    /// # })
+    /// ```
+    #[must_use]
+    pub fn snapshot_cookies(&self) -> CookieJar {
+        self.cookies()
+    }
+
+    /// Replaces all of the cookies stored with those given.
+    ///
+    /// Cookies not in the jar given are removed.
+    pub fn restore_cookies(&mut self, cookies: CookieJar) {
+        InnerServer::set_cookies(&mut self.inner, cookies)
+            .context("Trying to restore_cookies")
+            .unwrap()
+    }
+
     /// Clears all of the cookies stored internally.
     pub fn clear_cookies(&mut self) {
         InnerServer::clear_cookies(&mut self.inner)
-            .with_context(|| format!("Trying to clear_cookies"))
+            .with_context(|| "Trying to clear_cookies".to_string())
+            .unwrap()
+    }
+
+    /// Puts the `Server` back to how it was when it was created.
+    ///
+    /// This clears all of the cookies stored,
+    /// and undoes any calls to `Server::save_cookies` or `Server::do_not_save_cookies`.
+    /// Settings from the `ServerConfig`, and from building the `Server`
+    /// (such as `Server::with_base_path`), are kept.
+    ///
+    /// This is useful when sharing a `Server` between tests.
+    pub fn reset(&mut self) {
+        InnerServer::reset(&mut self.inner)
+            .context("Trying to reset")
             .unwrap()
     }
 
@@ -53,7 +432,7 @@ impl Server {
     /// will get replaced.
     pub fn add_cookies(&mut self, cookies: CookieJar) {
         InnerServer::add_cookies(&mut self.inner, cookies)
-            .with_context(|| format!("Trying to add_cookies"))
+            .with_context(|| "Trying to add_cookies".to_string())
             .unwrap()
     }
 
@@ -63,39 +442,129 @@ impl Server {
     /// then it will be replaced.
     pub fn add_cookie(&mut self, cookie: Cookie) {
         InnerServer::add_cookie(&mut self.inner, cookie)
-            .with_context(|| format!("Trying to add_cookie"))
+            .with_context(|| "Trying to add_cookie".to_string())
+            .unwrap()
+    }
+
+    /// Removes the cookie with the given name,
+    /// so it is no longer included on future requests.
+    ///
+    /// Other cookies are left untouched.
+    pub fn remove_cookie(&mut self, name: &str) {
+        InnerServer::remove_cookie(&mut self.inner, name)
+            .with_context(|| format!("Trying to remove_cookie '{}'", name))
             .unwrap()
     }
 
     /// Creates a HTTP GET request to the path.
-    pub fn get(&self, path: &str) -> Request {
+    pub fn get<P>(&self, path: P) -> Request
+    where
+        P: RequestPath,
+    {
         self.method(Method::GET, path)
     }
 
     /// Creates a HTTP POST request to the given path.
-    pub fn post(&self, path: &str) -> Request {
+    pub fn post<P>(&self, path: P) -> Request
+    where
+        P: RequestPath,
+    {
         self.method(Method::POST, path)
     }
 
     /// Creates a HTTP PATCH request to the path.
-    pub fn patch(&self, path: &str) -> Request {
+    pub fn patch<P>(&self, path: P) -> Request
+    where
+        P: RequestPath,
+    {
         self.method(Method::PATCH, path)
     }
 
     /// Creates a HTTP PUT request to the path.
-    pub fn put(&self, path: &str) -> Request {
+    pub fn put<P>(&self, path: P) -> Request
+    where
+        P: RequestPath,
+    {
         self.method(Method::PUT, path)
     }
 
     /// Creates a HTTP DELETE request to the path.
-    pub fn delete(&self, path: &str) -> Request {
+    pub fn delete<P>(&self, path: P) -> Request
+    where
+        P: RequestPath,
+    {
         self.method(Method::DELETE, path)
     }
 
+    /// Creates a HTTP HEAD request to the path.
+    pub fn head<P>(&self, path: P) -> Request
+    where
+        P: RequestPath,
+    {
+        self.method(Method::HEAD, path)
+    }
+
+    /// Creates a HTTP OPTIONS request to the path.
+    pub fn options<P>(&self, path: P) -> Request
+    where
+        P: RequestPath,
+    {
+        self.method(Method::OPTIONS, path)
+    }
+
+    /// Sends many HTTP GET requests to the path, all at the same time,
+    /// and waits for all of them to finish.
+    ///
+    /// The responses are returned in the order the requests were created.
+    /// This is useful for simple concurrency tests.
+    ///
+    /// Cookies returned are not saved, as the order they would be saved in is racy.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// # use ::axum::routing::get;
+    /// # use ::kantan::Server;
+    /// #
+    /// # let app = Router::new().route("/counter", get(|| async {}));
+    /// # let server = Server::new_with_router(app).unwrap();
+    /// let responses = server.get_many("/counter", 10).await;
+    /// assert_eq!(responses.len(), 10);
+    /// # })
+    /// ```
+    pub async fn get_many<P>(&self, path: P, count: usize) -> Vec<Response>
+    where
+        P: RequestPath,
+    {
+        let requests = (0..count).map(|_| self.get(&path).do_not_save_cookies().into_future());
+
+        join_all(requests).await
+    }
+
     /// Creates a HTTP request, to the path given, using the given method.
-    pub fn method(&self, method: Method, path: &str) -> Request {
+    ///
+    /// This can be used for methods without their own function,
+    /// including extension methods such as WebDAV's `PROPFIND`.
+    ///
+    /// ```rust
+    /// # ::tokio_test::block_on(async {
+    /// # use ::axum::Router;
+    /// use ::kantan::http::Method;
+    /// # use ::kantan::Server;
+    /// #
+    /// # let app = Router::new().fallback(|| async {});
+    /// # let server = Server::new_with_router(app).unwrap();
+    ///
+    /// let method = Method::from_bytes(b"PROPFIND").unwrap();
+    /// let response = server.method(method, "/files").await;
+    /// # })
+    /// ```
+    pub fn method<P>(&self, method: Method, path: P) -> Request
+    where
+        P: RequestPath,
+    {
         let debug_method = method.clone();
-        InnerServer::send(&self.inner, method, path)
+        InnerServer::send(&self.inner, method, &path)
             .with_context(|| {
                 format!(
                     "Trying to create internal request for {} {}",
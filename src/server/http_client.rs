@@ -0,0 +1,113 @@
+use ::hyper::body::Body;
+use ::hyper::client::connect::Connect;
+use ::hyper::client::Builder;
+use ::hyper::client::HttpConnector;
+use ::hyper::client::ResponseFuture;
+use ::hyper::http::Request as HyperRequest;
+use ::hyper::Client;
+use ::hyper_tls::HttpsConnector;
+
+#[cfg(unix)]
+use ::hyperlocal::UnixConnector;
+
+/// The Hyper clients used for sending requests.
+///
+/// This wraps the different transports a `Server` can be reached over.
+#[derive(Debug, Clone)]
+pub(crate) enum HttpClient {
+    /// Requests over TCP, using HTTP or HTTPS.
+    Tcp(ConfiguredClients<HttpsConnector<HttpConnector>>),
+
+    /// Requests over a Unix domain socket.
+    #[cfg(unix)]
+    Unix(ConfiguredClients<UnixConnector>),
+}
+
+impl HttpClient {
+    pub(crate) fn new_tcp() -> Self {
+        Self::new_tcp_with(Client::builder(), HttpsConnector::new())
+    }
+
+    pub(crate) fn new_tcp_with(builder: Builder, connector: HttpsConnector<HttpConnector>) -> Self {
+        Self::Tcp(ConfiguredClients::new(builder, connector))
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn new_unix() -> Self {
+        Self::Unix(ConfiguredClients::new(Client::builder(), UnixConnector))
+    }
+
+    /// A name for the transport this sends requests over, for error messages.
+    pub(crate) fn transport_name(&self) -> &'static str {
+        match self {
+            Self::Tcp(_) => "TCP",
+            #[cfg(unix)]
+            Self::Unix(_) => "Unix domain socket",
+        }
+    }
+
+    /// Sends the request, using the client with the options given.
+    ///
+    /// `is_http2_only` makes the client only speak HTTP/2 using prior knowledge,
+    /// and `is_setting_host` is if a missing `Host` header is added automatically.
+    pub(crate) fn request(
+        &self,
+        request: HyperRequest<Body>,
+        is_http2_only: bool,
+        is_setting_host: bool,
+    ) -> ResponseFuture {
+        match self {
+            Self::Tcp(clients) => clients.get(is_http2_only, is_setting_host).request(request),
+            #[cfg(unix)]
+            Self::Unix(clients) => clients.get(is_http2_only, is_setting_host).request(request),
+        }
+    }
+}
+
+/// A client for each of the options a `Request` can change,
+/// all built from the same `Builder` and connector.
+///
+/// These are built up front so each keeps its connection pool between requests.
+#[derive(Debug, Clone)]
+pub(crate) struct ConfiguredClients<C> {
+    default: Client<C>,
+    http2_only: Client<C>,
+    without_host: Client<C>,
+    http2_only_without_host: Client<C>,
+}
+
+impl<C> ConfiguredClients<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    fn new(builder: Builder, connector: C) -> Self {
+        // Options are only changed when asked for, so the rest of the builder is kept as is.
+        let build = |is_http2_only: bool, is_setting_host: bool| {
+            let mut builder = builder.clone();
+            if is_http2_only {
+                builder.http2_only(true);
+            }
+            if !is_setting_host {
+                builder.set_host(false);
+            }
+
+            builder.build::<_, Body>(connector.clone())
+        };
+
+        Self {
+            default: build(false, true),
+            http2_only: build(true, true),
+            without_host: build(false, false),
+            http2_only_without_host: build(true, false),
+        }
+    }
+
+    fn get(&self, is_http2_only: bool, is_setting_host: bool) -> &Client<C> {
+        match (is_http2_only, is_setting_host) {
+            (false, true) => &self.default,
+            (true, true) => &self.http2_only,
+            (false, false) => &self.without_host,
+            (true, false) => &self.http2_only_without_host,
+        }
+    }
+}
@@ -1,41 +1,185 @@
 use ::anyhow::anyhow;
 use ::anyhow::Context;
 use ::anyhow::Result;
+use ::axum::Router;
 use ::cookie::Cookie;
 use ::cookie::CookieJar;
+use ::hyper::http::HeaderName;
 use ::hyper::http::HeaderValue;
 use ::hyper::http::Method;
 use ::hyper::http::Uri;
+use ::std::net::Ipv4Addr;
+use ::std::net::SocketAddr;
+use ::std::net::TcpListener;
+#[cfg(unix)]
+use ::std::path::Path;
 use ::std::sync::Arc;
 use ::std::sync::Mutex;
+use ::std::time::Duration;
 
+use crate::expire_from_max_age;
+use crate::is_cookie_expired;
+use crate::HttpClient;
 use crate::Request;
 use crate::RequestConfig;
+use crate::RequestDetails;
+use crate::RequestPath;
+use crate::ServerConfig;
+use crate::ServerTask;
+
+const DEFAULT_SCHEME: &str = "http";
 
 /// The `InnerServer` is the real server that runs.
-#[derive(Debug)]
+///
+/// Cloning this shares the cookies and the running app,
+/// and copies everything else.
+#[derive(Debug, Clone)]
 pub(crate) struct InnerServer {
-    server_address: String,
-    cookies: CookieJar,
+    /// Only held onto, as the app is stopped when this is dropped.
+    _server_task: Option<Arc<ServerTask>>,
+    server_address: Uri,
+    client: HttpClient,
+    cookies: Arc<Mutex<CookieJar>>,
+    base_path: Option<String>,
     save_cookies: bool,
+    initial_save_cookies: bool,
     default_content_type: Option<String>,
+    default_headers: Vec<(HeaderName, HeaderValue)>,
+    default_timeout: Option<Duration>,
 }
 
 impl InnerServer {
     /// Creates a `Server` running your app on the address given.
-    pub(crate) fn new(server_address: String) -> Result<Self> {
+    pub(crate) fn new(server_address: String, config: ServerConfig) -> Result<Self> {
+        Self::new_with_server_task(server_address, HttpClient::new_tcp(), config, None)
+    }
+
+    /// Creates a `Server` sending requests to the Unix domain socket given.
+    #[cfg(unix)]
+    pub(crate) fn new_unix(socket_path: &Path, config: ServerConfig) -> Result<Self> {
+        let server_address: Uri = ::hyperlocal::Uri::new(socket_path, "/").into();
+
+        Self::new_with_server_task(
+            server_address.to_string(),
+            HttpClient::new_unix(),
+            config,
+            None,
+        )
+    }
+
+    /// Runs the given app on the port given on localhost,
+    /// and creates a `Server` pointing to it.
+    /// When the port is `0` a random free port is used.
+    ///
+    /// The app is stopped when this is dropped.
+    pub(crate) fn new_with_router(app: Router, port: u16, config: ServerConfig) -> Result<Self> {
+        let socket_address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let listener = TcpListener::bind(socket_address).with_context(|| {
+            format!(
+                "Failed to bind TcpListener to {}, is the port already in use?",
+                socket_address
+            )
+        })?;
+        let bound_address = listener
+            .local_addr()
+            .context("Failed to read address of TcpListener")?;
+
+        let server_task = ServerTask::spawn(listener, app)?;
+
+        let server_address = format!("http://{}", bound_address);
+        Self::new_with_server_task(
+            server_address,
+            HttpClient::new_tcp(),
+            config,
+            Some(server_task),
+        )
+    }
+
+    fn new_with_server_task(
+        server_address: String,
+        client: HttpClient,
+        config: ServerConfig,
+        server_task: Option<ServerTask>,
+    ) -> Result<Self> {
+        let server_address: Uri = server_address
+            .as_str()
+            .try_into()
+            .with_context(|| format!("Failed to parse server address '{}'", server_address))?;
+
         let test_server = Self {
+            _server_task: server_task.map(Arc::new),
             server_address,
-            cookies: CookieJar::new(),
-            save_cookies: false,
-            default_content_type: None,
+            client,
+            cookies: Arc::new(Mutex::new(CookieJar::new())),
+            base_path: None,
+            save_cookies: config.save_cookies,
+            initial_save_cookies: config.save_cookies,
+            default_content_type: config
+                .default_content_type
+                .map(|content_type| content_type.trim().to_string()),
+            default_headers: config.default_headers,
+            default_timeout: config.default_timeout,
         };
 
         Ok(test_server)
     }
 
-    pub(crate) fn cookies<'a>(&'a self) -> &'a CookieJar {
-        &self.cookies
+    pub(crate) fn server_address(&self) -> &Uri {
+        &self.server_address
+    }
+
+    /// Returns the server address as a string, without a trailing slash.
+    pub(crate) fn base_url(&self) -> Result<String> {
+        let scheme = self.server_address.scheme_str().unwrap_or(DEFAULT_SCHEME);
+        let authority = self.server_address.authority().with_context(|| {
+            format!("Server address '{}' is missing a host", self.server_address)
+        })?;
+        let path = self.server_address.path().trim_end_matches('/');
+
+        Ok(format!("{}://{}{}", scheme, authority, path))
+    }
+
+    /// Returns the port of the server address,
+    /// falling back to the default port for its scheme.
+    pub(crate) fn port(&self) -> Result<u16> {
+        if let Some(port) = self.server_address.port_u16() {
+            return Ok(port);
+        }
+
+        match self.server_address.scheme_str().unwrap_or(DEFAULT_SCHEME) {
+            "http" => Ok(80),
+            "https" => Ok(443),
+            scheme => Err(anyhow!(
+                "Server address '{}' has no port, and scheme '{}' has no default port",
+                self.server_address,
+                scheme
+            )),
+        }
+    }
+
+    pub(crate) fn client(&self) -> &HttpClient {
+        &self.client
+    }
+
+    /// Returns a copy of the cookies stored.
+    pub(crate) fn cookies(&self) -> Result<CookieJar> {
+        self.with_cookies("cookies", |cookies| cookies.clone())
+    }
+
+    /// Runs the action given on the cookies stored,
+    /// which are shared with any clones of this.
+    fn with_cookies<F, R>(&self, name: &str, some_action: F) -> Result<R>
+    where
+        F: FnOnce(&mut CookieJar) -> R,
+    {
+        let mut cookies_locked = self
+            .cookies
+            .lock()
+            .map_err(|err| anyhow!("Failed to lock cookies for `{}`, {:?}", name, err,))?;
+
+        let result = some_action(&mut cookies_locked);
+
+        Ok(result)
     }
 
     /// Adds the given cookies.
@@ -49,27 +193,52 @@ impl InnerServer {
         I: Iterator<Item = &'a HeaderValue>,
     {
         InnerServer::with_this_mut(this, "add_cookies_by_header", |this| {
+            let mut cookies = vec![];
             for cookie_header in cookie_headers {
                 let cookie_header_str = cookie_header
                     .to_str()
-                    .context(&"Reading cookie header for storing in the `Server`")
+                    .context("Reading cookie header for storing in the `Server`")
                     .unwrap();
 
-                let cookie: Cookie<'static> = Cookie::parse(cookie_header_str)?.into_owned();
-                this.cookies.add(cookie);
+                let mut cookie: Cookie<'static> = Cookie::parse(cookie_header_str)?.into_owned();
+                expire_from_max_age(&mut cookie);
+                cookies.push(cookie);
             }
 
-            Ok(()) as Result<()>
+            this.with_cookies("add_cookies_by_header", |jar| {
+                for cookie in cookies {
+                    // Servers delete cookies by sending them already expired.
+                    if is_cookie_expired(&cookie) {
+                        jar.force_remove(&cookie);
+                    } else {
+                        jar.add(cookie);
+                    }
+                }
+            })
         })?
     }
 
-    /// Adds the given cookies.
-    ///
-    /// They will be stored over the top of the existing cookies.
+    /// Removes all of the cookies stored.
     pub(crate) fn clear_cookies(this: &mut Arc<Mutex<Self>>) -> Result<()> {
         InnerServer::with_this_mut(this, "clear_cookies", |this| {
-            this.cookies = CookieJar::new();
-        })
+            this.with_cookies("clear_cookies", |cookies| *cookies = CookieJar::new())
+        })?
+    }
+
+    /// Removes all of the cookies stored,
+    /// and puts saving cookies back to how it was configured.
+    pub(crate) fn reset(this: &mut Arc<Mutex<Self>>) -> Result<()> {
+        InnerServer::with_this_mut(this, "reset", |this| {
+            this.save_cookies = this.initial_save_cookies;
+            this.with_cookies("reset", |cookies| *cookies = CookieJar::new())
+        })?
+    }
+
+    /// Replaces all of the cookies with those given.
+    pub(crate) fn set_cookies(this: &mut Arc<Mutex<Self>>, cookies: CookieJar) -> Result<()> {
+        InnerServer::with_this_mut(this, "set_cookies", |this| {
+            this.with_cookies("set_cookies", |jar| *jar = cookies)
+        })?
     }
 
     /// Adds the given cookies.
@@ -77,40 +246,138 @@ impl InnerServer {
     /// They will be stored over the top of the existing cookies.
     pub(crate) fn add_cookies(this: &mut Arc<Mutex<Self>>, cookies: CookieJar) -> Result<()> {
         InnerServer::with_this_mut(this, "add_cookies", |this| {
-            for cookie in cookies.iter() {
-                this.cookies.add(cookie.to_owned());
-            }
-        })
+            this.with_cookies("add_cookies", |jar| {
+                for cookie in cookies.iter() {
+                    jar.add(cookie.to_owned());
+                }
+            })
+        })?
     }
 
+    /// Adds the given cookie.
+    ///
+    /// It will replace any existing cookie with the same name.
     pub(crate) fn add_cookie(this: &mut Arc<Mutex<Self>>, cookie: Cookie) -> Result<()> {
         InnerServer::with_this_mut(this, "add_cookie", |this| {
-            this.cookies.add(cookie.into_owned());
+            this.with_cookies("add_cookie", |cookies| cookies.add(cookie.into_owned()))
+        })?
+    }
+
+    /// Removes all cookies which have expired.
+    pub(crate) fn prune_expired_cookies(this: &mut Arc<Mutex<Self>>) -> Result<()> {
+        InnerServer::with_this_mut(this, "prune_expired_cookies", |this| {
+            this.with_cookies("prune_expired_cookies", |cookies| {
+                let expired_cookies: Vec<Cookie<'static>> = cookies
+                    .iter()
+                    .filter(|cookie| is_cookie_expired(cookie))
+                    .map(|cookie| cookie.clone().into_owned())
+                    .collect();
+
+                for expired_cookie in expired_cookies {
+                    cookies.force_remove(&expired_cookie);
+                }
+            })
+        })?
+    }
+
+    /// Removes the cookie with the given name, if it is stored.
+    pub(crate) fn remove_cookie(this: &mut Arc<Mutex<Self>>, name: &str) -> Result<()> {
+        InnerServer::with_this_mut(this, "remove_cookie", |this| {
+            this.with_cookies("remove_cookie", |cookies| {
+                cookies.force_remove(&Cookie::named(name.to_string()))
+            })
+        })?
+    }
+
+    /// Sets the path to prefix onto the path of all requests.
+    pub(crate) fn set_base_path(this: &mut Arc<Mutex<Self>>, base_path: &str) -> Result<()> {
+        InnerServer::with_this_mut(this, "set_base_path", |this| {
+            this.base_path = Some(base_path.to_string());
+        })
+    }
+
+    /// Sets the content type used by requests, when they do not set their own.
+    pub(crate) fn set_default_content_type(
+        this: &mut Arc<Mutex<Self>>,
+        content_type: &str,
+    ) -> Result<()> {
+        InnerServer::with_this_mut(this, "set_default_content_type", |this| {
+            this.default_content_type = Some(content_type.trim().to_string());
         })
     }
 
-    pub(crate) fn build_request_config(
+    /// Sets the timeout used by requests, when they do not set their own.
+    pub(crate) fn set_default_timeout(
+        this: &mut Arc<Mutex<Self>>,
+        default_timeout: Duration,
+    ) -> Result<()> {
+        InnerServer::with_this_mut(this, "set_default_timeout", |this| {
+            this.default_timeout = Some(default_timeout);
+        })
+    }
+
+    /// Sets the client used for sending requests.
+    ///
+    /// The client must use the same transport as the one it replaces.
+    pub(crate) fn set_client(this: &mut Arc<Mutex<Self>>, client: HttpClient) -> Result<()> {
+        InnerServer::with_this_mut(this, "set_client", |this| {
+            if this.client.transport_name() != client.transport_name() {
+                return Err(anyhow!(
+                    "Cannot use a {} client, as the Server sends requests over a {}",
+                    client.transport_name(),
+                    this.client.transport_name()
+                ));
+            }
+
+            this.client = client;
+            Ok(())
+        })?
+    }
+
+    /// Sets if cookies returned should be saved by default.
+    pub(crate) fn set_save_cookies(this: &mut Arc<Mutex<Self>>, save_cookies: bool) -> Result<()> {
+        InnerServer::with_this_mut(this, "set_save_cookies", |this| {
+            this.save_cookies = save_cookies;
+        })
+    }
+
+    pub(crate) fn build_request_details<P>(
         this: &Arc<Mutex<Self>>,
         method: Method,
-        path: &str,
-    ) -> Result<RequestConfig> {
-        InnerServer::with_this(this, "request_config", |this| {
-            let request_path = build_request_path(&this.server_address, path)?;
-            let config = RequestConfig {
+        path: &P,
+    ) -> Result<RequestDetails>
+    where
+        P: RequestPath + ?Sized,
+    {
+        InnerServer::with_this(this, "request_details", |this| {
+            let request_path =
+                build_request_path(&this.server_address, this.base_path.as_deref(), path)?;
+            let details = RequestDetails {
                 method,
                 request_path,
-                save_cookies: this.save_cookies,
-                content_type: this.default_content_type.clone(),
             };
 
-            Ok(config)
+            Ok(details)
         })?
     }
 
-    pub(crate) fn send(this: &Arc<Mutex<Self>>, method: Method, path: &str) -> Result<Request> {
-        let config = InnerServer::build_request_config(this, method, path)?;
+    pub(crate) fn build_request_config(this: &Arc<Mutex<Self>>) -> Result<RequestConfig> {
+        InnerServer::with_this(this, "request_config", |this| RequestConfig {
+            save_cookies: this.save_cookies,
+            content_type: this.default_content_type.clone(),
+            default_headers: this.default_headers.clone(),
+            timeout: this.default_timeout,
+        })
+    }
 
-        Request::new(this.clone(), config)
+    pub(crate) fn send<P>(this: &Arc<Mutex<Self>>, method: Method, path: &P) -> Result<Request>
+    where
+        P: RequestPath + ?Sized,
+    {
+        let details = InnerServer::build_request_details(this, method, path)?;
+        let config = InnerServer::build_request_config(this)?;
+
+        Request::new(this.clone(), details, config)
     }
 
     pub(crate) fn with_this<F, R>(this: &Arc<Mutex<Self>>, name: &str, some_action: F) -> Result<R>
@@ -144,16 +411,306 @@ impl InnerServer {
     }
 }
 
-fn build_request_path(root: &str, sub_path: &str) -> Result<Uri> {
-    if sub_path.is_empty() {
-        return Ok(root.try_into()?);
+fn build_request_path<P>(root: &Uri, base_path: Option<&str>, sub_path: &P) -> Result<Uri>
+where
+    P: RequestPath + ?Sized,
+{
+    let (sub_path, query) = sub_path.to_path_and_query()?;
+    let scheme = root.scheme_str().unwrap_or(DEFAULT_SCHEME);
+    let authority = root
+        .authority()
+        .with_context(|| format!("Server address '{}' is missing a host", root))?;
+
+    let path = join_paths(&[root.path(), base_path.unwrap_or_default(), sub_path]);
+    let path = encode_path(&path);
+    let mut full_path = format!("{}://{}{}", scheme, authority, path);
+    if let Some(query) = query {
+        full_path.push('?');
+        full_path.push_str(query);
+    }
+
+    Ok(full_path.try_into()?)
+}
+
+/// Joins the paths given, with exactly one `/` between each of them.
+///
+/// The result always starts with a `/`,
+/// and ends with one if the last path does.
+pub(crate) fn join_paths(paths: &[&str]) -> String {
+    let mut full_path = String::new();
+    for path in paths {
+        let trimmed_path = path.trim_matches('/');
+        if !trimmed_path.is_empty() {
+            full_path.push('/');
+            full_path.push_str(trimmed_path);
+        }
+    }
+
+    let has_trailing_slash = paths.last().is_some_and(|path| path.ends_with('/'));
+    if full_path.is_empty() || has_trailing_slash {
+        full_path.push('/');
+    }
+
+    full_path
+}
+
+/// Percent-encodes any characters which are not allowed in a url path,
+/// such as spaces.
+///
+/// The `/` separators are left as they are,
+/// and anything already percent-encoded is not encoded a second time.
+pub(crate) fn encode_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut encoded = String::with_capacity(path.len());
+
+    for (i, byte) in bytes.iter().enumerate() {
+        let is_allowed = byte.is_ascii_alphanumeric()
+            || b"-._~!$&'()*+,;=:@/".contains(byte)
+            || (*byte == b'%' && is_percent_encoded(&bytes[i + 1..]));
+
+        if is_allowed {
+            encoded.push(*byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded
+}
+
+fn is_percent_encoded(rest: &[u8]) -> bool {
+    rest.len() >= 2 && rest[0].is_ascii_hexdigit() && rest[1].is_ascii_hexdigit()
+}
+
+#[cfg(test)]
+mod test_build_request_path {
+    use super::*;
+
+    #[test]
+    fn it_should_use_https_when_in_server_address() {
+        let root = Uri::from_static("https://example.com");
+        let path = build_request_path(&root, None, "/users").unwrap();
+
+        assert_eq!(path.to_string(), "https://example.com/users");
+    }
+
+    #[test]
+    fn it_should_use_http_when_in_server_address() {
+        let root = Uri::from_static("http://127.0.0.1:3000");
+        let path = build_request_path(&root, None, "/users").unwrap();
+
+        assert_eq!(path.to_string(), "http://127.0.0.1:3000/users");
+    }
+
+    #[test]
+    fn it_should_default_to_http_when_server_address_has_no_scheme() {
+        let root = Uri::from_static("127.0.0.1:3000");
+        let path = build_request_path(&root, None, "users").unwrap();
+
+        assert_eq!(path.to_string(), "http://127.0.0.1:3000/users");
+    }
+
+    #[test]
+    fn it_should_return_root_for_empty_sub_path() {
+        let root = Uri::from_static("https://example.com");
+        let path = build_request_path(&root, None, "").unwrap();
+
+        assert_eq!(path.to_string(), "https://example.com/");
+    }
+
+    #[test]
+    fn it_should_keep_query_in_sub_path() {
+        let root = Uri::from_static("http://example.com");
+        let path = build_request_path(&root, None, "/users?page=2").unwrap();
+
+        assert_eq!(path.to_string(), "http://example.com/users?page=2");
+    }
+
+    #[test]
+    fn it_should_encode_spaces_in_sub_path() {
+        let root = Uri::from_static("http://example.com");
+        let path = build_request_path(&root, None, "/users/john doe?page=2").unwrap();
+
+        assert_eq!(
+            path.to_string(),
+            "http://example.com/users/john%20doe?page=2"
+        );
+    }
+
+    #[test]
+    fn it_should_prefix_base_path() {
+        let root = Uri::from_static("http://example.com");
+        let path = build_request_path(&root, Some("/api"), "/users").unwrap();
+
+        assert_eq!(path.to_string(), "http://example.com/api/users");
+    }
+
+    #[test]
+    fn it_should_prefix_base_path_with_trailing_slash() {
+        let root = Uri::from_static("http://example.com");
+        let path = build_request_path(&root, Some("/api/"), "/users").unwrap();
+
+        assert_eq!(path.to_string(), "http://example.com/api/users");
+    }
+
+    #[test]
+    fn it_should_prefix_base_path_without_slashes() {
+        let root = Uri::from_static("http://example.com");
+        let path = build_request_path(&root, Some("api"), "users").unwrap();
+
+        assert_eq!(path.to_string(), "http://example.com/api/users");
     }
 
-    if sub_path.starts_with("/") {
-        let full_path = format!("{}{}", root, sub_path).try_into()?;
-        return Ok(full_path);
+    #[test]
+    fn it_should_prefix_base_path_after_server_address_path() {
+        let root = Uri::from_static("http://example.com/v1/");
+        let path = build_request_path(&root, Some("/api"), "users").unwrap();
+
+        assert_eq!(path.to_string(), "http://example.com/v1/api/users");
     }
 
-    let full_path = format!("{}/{}", root, sub_path).try_into()?;
-    Ok(full_path)
+    #[test]
+    fn it_should_reject_full_url_as_sub_path() {
+        let root = Uri::from_static("http://example.com");
+        let result = build_request_path(&root, None, "http://other.com/users");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_allow_url_within_query_of_sub_path() {
+        let root = Uri::from_static("http://example.com");
+        let path = build_request_path(&root, None, "/login?next=http://example.com/").unwrap();
+
+        assert_eq!(
+            path.to_string(),
+            "http://example.com/login?next=http://example.com/"
+        );
+    }
+
+    #[test]
+    fn it_should_keep_trailing_slash_of_sub_path() {
+        let root = Uri::from_static("http://example.com");
+        let path = build_request_path(&root, Some("/api"), "/users/").unwrap();
+
+        assert_eq!(path.to_string(), "http://example.com/api/users/");
+    }
+}
+
+#[cfg(test)]
+mod test_join_paths {
+    use super::*;
+
+    #[test]
+    fn it_should_join_with_empty_base_path() {
+        assert_eq!(join_paths(&["", ""]), "/");
+        assert_eq!(join_paths(&["", "/"]), "/");
+        assert_eq!(join_paths(&["", "foo"]), "/foo");
+        assert_eq!(join_paths(&["", "/foo"]), "/foo");
+    }
+
+    #[test]
+    fn it_should_join_with_base_path_with_slashes() {
+        assert_eq!(join_paths(&["/api/", ""]), "/api");
+        assert_eq!(join_paths(&["/api/", "/"]), "/api/");
+        assert_eq!(join_paths(&["/api/", "foo"]), "/api/foo");
+        assert_eq!(join_paths(&["/api/", "/foo"]), "/api/foo");
+    }
+
+    #[test]
+    fn it_should_join_with_base_path_without_slashes() {
+        assert_eq!(join_paths(&["api", ""]), "/api");
+        assert_eq!(join_paths(&["api", "/"]), "/api/");
+        assert_eq!(join_paths(&["api", "foo"]), "/api/foo");
+        assert_eq!(join_paths(&["api", "/foo"]), "/api/foo");
+    }
+
+    #[test]
+    fn it_should_collapse_repeated_slashes_between_paths() {
+        assert_eq!(join_paths(&["/api//", "//foo"]), "/api/foo");
+        assert_eq!(join_paths(&["//", "//"]), "/");
+    }
+
+    #[test]
+    fn it_should_keep_trailing_slash_of_last_path() {
+        assert_eq!(join_paths(&["/api", "foo/"]), "/api/foo/");
+        assert_eq!(join_paths(&["/", "/api/", "/foo/"]), "/api/foo/");
+    }
+
+    #[test]
+    fn it_should_keep_slashes_within_a_path() {
+        assert_eq!(join_paths(&["/api", "users/123"]), "/api/users/123");
+    }
+}
+
+#[cfg(test)]
+mod test_encode_path {
+    use super::*;
+
+    #[test]
+    fn it_should_leave_plain_paths_unchanged() {
+        assert_eq!(encode_path("/users/123"), "/users/123");
+    }
+
+    #[test]
+    fn it_should_encode_spaces() {
+        assert_eq!(encode_path("/users/john doe"), "/users/john%20doe");
+    }
+
+    #[test]
+    fn it_should_not_double_encode() {
+        assert_eq!(encode_path("/users/john%20doe"), "/users/john%20doe");
+    }
+
+    #[test]
+    fn it_should_encode_percent_not_followed_by_hex() {
+        assert_eq!(encode_path("/discount/50%"), "/discount/50%25");
+        assert_eq!(encode_path("/discount/50%off"), "/discount/50%25off");
+    }
+
+    #[test]
+    fn it_should_encode_reserved_and_non_ascii_characters() {
+        assert_eq!(encode_path("/tags/c#"), "/tags/c%23");
+        assert_eq!(encode_path("/files/\"a\""), "/files/%22a%22");
+        assert_eq!(encode_path("/cities/zürich"), "/cities/z%C3%BCrich");
+    }
+
+    #[test]
+    fn it_should_leave_sub_delimiters_unchanged() {
+        assert_eq!(encode_path("/users/a:b@c;d=e"), "/users/a:b@c;d=e");
+    }
+}
+
+#[cfg(test)]
+mod test_build_request {
+    use super::*;
+
+    fn new_inner_server() -> Arc<Mutex<InnerServer>> {
+        let inner_server =
+            InnerServer::new("http://example.com".to_string(), ServerConfig::default()).unwrap();
+
+        Arc::new(Mutex::new(inner_server))
+    }
+
+    #[test]
+    fn it_should_build_method_and_path() {
+        let inner_server = new_inner_server();
+        let details =
+            InnerServer::build_request_details(&inner_server, Method::GET, "/users").unwrap();
+
+        assert_eq!(details.method, Method::GET);
+        assert_eq!(details.request_path.to_string(), "http://example.com/users");
+    }
+
+    #[test]
+    fn it_should_use_current_save_cookies_from_server() {
+        let mut inner_server = new_inner_server();
+
+        let config = InnerServer::build_request_config(&inner_server).unwrap();
+        assert!(!config.save_cookies);
+
+        InnerServer::set_save_cookies(&mut inner_server, true).unwrap();
+        let config = InnerServer::build_request_config(&inner_server).unwrap();
+        assert!(config.save_cookies);
+    }
 }
@@ -0,0 +1,114 @@
+use ::anyhow::anyhow;
+use ::anyhow::Result;
+use ::hyper::http::uri::PathAndQuery;
+use ::hyper::http::Uri;
+use ::std::fmt::Display;
+
+/// A path which a request can be made to,
+/// as taken by `Server::get`, `Server::post`, and the other request methods.
+///
+/// This is implemented for `&str`, `String`, and `Uri`,
+/// along with references to them.
+///
+/// Full urls, with a scheme such as `http://` or a host, are rejected.
+pub trait RequestPath: Display {
+    /// Returns the path, and the query if there is one.
+    fn to_path_and_query(&self) -> Result<(&str, Option<&str>)>;
+}
+
+impl RequestPath for str {
+    fn to_path_and_query(&self) -> Result<(&str, Option<&str>)> {
+        if has_scheme(self) {
+            return Err(anyhow!(
+                "Request path '{}' is a full url, expected only the path",
+                self
+            ));
+        }
+
+        match self.split_once('?') {
+            Some((path, query)) => Ok((path, Some(query))),
+            None => Ok((self, None)),
+        }
+    }
+}
+
+impl RequestPath for String {
+    fn to_path_and_query(&self) -> Result<(&str, Option<&str>)> {
+        self.as_str().to_path_and_query()
+    }
+}
+
+impl RequestPath for Uri {
+    fn to_path_and_query(&self) -> Result<(&str, Option<&str>)> {
+        if self.scheme().is_some() || self.authority().is_some() {
+            return Err(anyhow!(
+                "Request path '{}' is a full url, expected only the path",
+                self
+            ));
+        }
+
+        Ok((self.path(), self.query()))
+    }
+}
+
+impl RequestPath for PathAndQuery {
+    fn to_path_and_query(&self) -> Result<(&str, Option<&str>)> {
+        Ok((self.path(), self.query()))
+    }
+}
+
+impl<P> RequestPath for &P
+where
+    P: RequestPath + ?Sized,
+{
+    fn to_path_and_query(&self) -> Result<(&str, Option<&str>)> {
+        (**self).to_path_and_query()
+    }
+}
+
+/// Returns true if the path starts with a scheme, such as `http://`.
+fn has_scheme(path: &str) -> bool {
+    path.split_once("://").is_some_and(|(scheme, _)| {
+        !scheme.is_empty()
+            && scheme
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || b"+-.".contains(&byte))
+    })
+}
+
+#[cfg(test)]
+mod test_to_path_and_query {
+    use super::*;
+
+    #[test]
+    fn it_should_split_query_from_str() {
+        let (path, query) = "/users?page=2".to_path_and_query().unwrap();
+
+        assert_eq!(path, "/users");
+        assert_eq!(query, Some("page=2"));
+    }
+
+    #[test]
+    fn it_should_split_query_from_uri() {
+        let uri = Uri::from_static("/users?page=2");
+        let (path, query) = uri.to_path_and_query().unwrap();
+
+        assert_eq!(path, "/users");
+        assert_eq!(query, Some("page=2"));
+    }
+
+    #[test]
+    fn it_should_reject_uri_with_host() {
+        let uri = Uri::from_static("http://example.com/users");
+        let result = uri.to_path_and_query();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_reject_str_with_scheme() {
+        let result = "http://example.com/users".to_path_and_query();
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::axum::Router;
+use ::hyper::rt::Executor;
+use ::std::future::Future;
+use ::std::net::TcpListener;
+use ::std::sync::Arc;
+use ::std::sync::Mutex;
+use ::std::time::Duration;
+use ::tokio::runtime::Handle;
+use ::tokio::sync::oneshot::channel;
+use ::tokio::sync::oneshot::Sender;
+use ::tokio::task::JoinHandle;
+use ::tokio::task::JoinSet;
+use ::tokio::time::sleep;
+
+/// How long open requests are given to finish, once the app is stopped.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+/// An app running in the background, for a `Server` created from a `Router`.
+///
+/// The app is stopped when this is dropped. Open requests are given
+/// a grace period to finish, after which they are stopped as well.
+#[derive(Debug)]
+pub(crate) struct ServerTask {
+    shutdown: Option<Sender<()>>,
+    handle: JoinHandle<()>,
+    connections: ConnectionExecutor,
+}
+
+impl ServerTask {
+    /// Runs the app given on the listener, in the background.
+    ///
+    /// This errors when called outside of a Tokio runtime.
+    pub(crate) fn spawn(listener: TcpListener, app: Router) -> Result<Self> {
+        let runtime = Handle::try_current()
+            .context("Running an app requires a Tokio runtime, and none was found")?;
+        let connections = ConnectionExecutor::default();
+
+        // The app runs until a message is sent, or the sender is dropped.
+        let (shutdown_sender, shutdown_receiver) = channel::<()>();
+        let server = ::axum::Server::from_tcp(listener)
+            .context("Failed to create ::axum::Server for the app")?
+            .executor(connections.clone())
+            .serve(app.into_make_service())
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_receiver.await;
+            });
+        let handle = runtime.spawn(async move {
+            server.await.expect("Expect server to start serving");
+        });
+
+        Ok(Self {
+            shutdown: Some(shutdown_sender),
+            handle,
+            connections,
+        })
+    }
+}
+
+impl Drop for ServerTask {
+    fn drop(&mut self) {
+        // Stops accepting new connections, and releases the port.
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+
+        // Requests which never finish would keep the app running,
+        // so it is stopped regardless once the grace period is over.
+        let handle = self.handle.abort_handle();
+        let connections = self.connections.clone();
+        let stop = move || {
+            handle.abort();
+            connections.abort_all();
+        };
+
+        match Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(async move {
+                    sleep(SHUTDOWN_GRACE_PERIOD).await;
+                    stop();
+                });
+            }
+            Err(_) => stop(),
+        }
+    }
+}
+
+/// Runs each connection to the app as its own task,
+/// keeping hold of them so they can be stopped along with the app.
+#[derive(Debug, Clone, Default)]
+struct ConnectionExecutor {
+    tasks: Arc<Mutex<JoinSet<()>>>,
+}
+
+impl ConnectionExecutor {
+    fn abort_all(&self) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.abort_all();
+        }
+    }
+}
+
+impl<F> Executor<F> for ConnectionExecutor
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    fn execute(&self, future: F) {
+        let mut tasks = self
+            .tasks
+            .lock()
+            .expect("Expect connection tasks to not be poisoned");
+
+        // Finished connections are cleared out as new ones arrive.
+        while tasks.try_join_next().is_some() {}
+        tasks.spawn(future);
+    }
+}
@@ -0,0 +1,35 @@
+use ::hyper::http::HeaderName;
+use ::hyper::http::HeaderValue;
+use ::std::time::Duration;
+
+/// The basic setup for the `Server`.
+#[derive(Debug, Clone, Default)]
+pub struct ServerConfig {
+    /// Set the default content type for all requests created by the `Server`.
+    ///
+    /// This overrides the default 'best efforts' approach of requests.
+    /// Any whitespace around the content type is removed.
+    pub default_content_type: Option<String>,
+
+    /// Set for the server to save cookies that are returned,
+    /// for use in future requests.
+    ///
+    /// This is useful for automatically saving session cookies (and similar)
+    /// like a browser would do.
+    ///
+    /// **Defaults** to false (being turned off).
+    pub save_cookies: bool,
+
+    /// Headers to include on all requests created by the `Server`,
+    /// such as `Accept`.
+    ///
+    /// **Defaults** to no headers.
+    pub default_headers: Vec<(HeaderName, HeaderValue)>,
+
+    /// The longest time a request can take, before it fails.
+    ///
+    /// This can be overridden on each request, by calling `Request::timeout`.
+    ///
+    /// **Defaults** to no timeout.
+    pub default_timeout: Option<Duration>,
+}